@@ -0,0 +1,252 @@
+use std::{fmt::Display, iter::Peekable};
+
+/// Which way the sign string is read relative to the numbers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Forward,
+    /// Read the signs back to front, mirroring parentheses so they still nest the same way.
+    Reverse,
+}
+
+/// How to lay the numbers and signs out into an expression.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Layout {
+    /// How many consecutive numbers make up each operand, read as decimal digits.
+    pub group: usize,
+    pub direction: Direction,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Token {
+    Num(i64),
+    Op(u8),
+    Open,
+    Close,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExprError {
+    UnknownSymbol(char),
+    MisplacedParen,
+    UnbalancedParens,
+    Empty,
+    DivisionByZero,
+    Overflow,
+}
+
+impl Display for ExprError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownSymbol(c) => write!(f, "unknown symbol {c:?}"),
+            Self::MisplacedParen => write!(f, "parenthesis in the wrong place"),
+            Self::UnbalancedParens => write!(f, "unbalanced parentheses"),
+            Self::Empty => write!(f, "no numbers to evaluate"),
+            Self::DivisionByZero => write!(f, "division by zero"),
+            Self::Overflow => write!(f, "arithmetic overflow"),
+        }
+    }
+}
+
+impl std::error::Error for ExprError {}
+
+/// Evaluate the numbers with the signs between them, with the usual precedence: parentheses first, then `*` and `/`,
+/// then `+` and `-`, each left to right. Division truncates towards zero.
+///
+/// Each operator takes the next operand, `(` opens a group before an operand and `)` closes one after it. Like
+/// zipping, anything left over once either the numbers or the operators run out is ignored.
+pub fn evaluate(numbers: &[i64], signs: &str, layout: Layout) -> Result<i64, ExprError> {
+    let tokens = tokenize(numbers, signs, layout)?;
+    let mut tokens = tokens.into_iter().peekable();
+    let value = parse_sum(&mut tokens)?;
+    match tokens.next() {
+        None => Ok(value),
+        Some(_) => Err(ExprError::UnbalancedParens),
+    }
+}
+
+fn tokenize(numbers: &[i64], signs: &str, layout: Layout) -> Result<Vec<Token>, ExprError> {
+    let group = layout.group.max(1);
+    let mut operands = numbers.chunks_exact(group).map(|digits| {
+        digits
+            .iter()
+            .try_fold(0i64, |acc, &d| acc.checked_mul(10)?.checked_add(d))
+            .ok_or(ExprError::Overflow)
+    });
+
+    let symbols: Box<dyn Iterator<Item = char>> = match layout.direction {
+        Direction::Forward => Box::new(signs.chars()),
+        Direction::Reverse => Box::new(signs.chars().rev().map(|c| match c {
+            '(' => ')',
+            ')' => '(',
+            c => c,
+        })),
+    };
+
+    let mut tokens = Vec::new();
+    let mut expecting_operand = true;
+    for symbol in symbols {
+        if symbol == '(' {
+            if !expecting_operand {
+                return Err(ExprError::MisplacedParen);
+            }
+            tokens.push(Token::Open);
+            continue;
+        }
+
+        if expecting_operand {
+            let Some(operand) = operands.next() else {
+                break;
+            };
+            tokens.push(Token::Num(operand?));
+            expecting_operand = false;
+        }
+
+        match symbol {
+            ')' => tokens.push(Token::Close),
+            '+' | '-' | '*' | '/' => {
+                tokens.push(Token::Op(symbol as u8));
+                expecting_operand = true;
+            }
+            c => return Err(ExprError::UnknownSymbol(c)),
+        }
+    }
+
+    if expecting_operand {
+        match operands.next() {
+            Some(operand) => tokens.push(Token::Num(operand?)),
+            // Out of numbers: drop the operator and any parentheses still waiting for an operand.
+            None => {
+                while matches!(tokens.last(), Some(Token::Op(_) | Token::Open)) {
+                    tokens.pop();
+                }
+            }
+        }
+    }
+
+    if tokens.is_empty() {
+        return Err(ExprError::Empty);
+    }
+    Ok(tokens)
+}
+
+type Tokens = Peekable<std::vec::IntoIter<Token>>;
+
+fn parse_sum(tokens: &mut Tokens) -> Result<i64, ExprError> {
+    let mut acc = parse_product(tokens)?;
+    while let Some(&Token::Op(op @ (b'+' | b'-'))) = tokens.peek() {
+        tokens.next();
+        let rhs = parse_product(tokens)?;
+        acc = match op {
+            b'+' => acc.checked_add(rhs),
+            _ => acc.checked_sub(rhs),
+        }
+        .ok_or(ExprError::Overflow)?;
+    }
+    Ok(acc)
+}
+
+fn parse_product(tokens: &mut Tokens) -> Result<i64, ExprError> {
+    let mut acc = parse_atom(tokens)?;
+    while let Some(&Token::Op(op @ (b'*' | b'/'))) = tokens.peek() {
+        tokens.next();
+        let rhs = parse_atom(tokens)?;
+        acc = match op {
+            b'*' => acc.checked_mul(rhs).ok_or(ExprError::Overflow)?,
+            _ if rhs == 0 => return Err(ExprError::DivisionByZero),
+            _ => acc.checked_div(rhs).ok_or(ExprError::Overflow)?,
+        };
+    }
+    Ok(acc)
+}
+
+fn parse_atom(tokens: &mut Tokens) -> Result<i64, ExprError> {
+    match tokens.next() {
+        Some(Token::Num(n)) => Ok(n),
+        Some(Token::Open) => {
+            let value = parse_sum(tokens)?;
+            match tokens.next() {
+                Some(Token::Close) => Ok(value),
+                _ => Err(ExprError::UnbalancedParens),
+            }
+        }
+        _ => Err(ExprError::UnbalancedParens),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORWARD: Layout = Layout {
+        group: 1,
+        direction: Direction::Forward,
+    };
+    const REVERSE: Layout = Layout {
+        group: 1,
+        direction: Direction::Reverse,
+    };
+
+    #[test]
+    fn precedence_and_associativity() {
+        assert_eq!(evaluate(&[1, 2, 3], "+*", FORWARD), Ok(7));
+        assert_eq!(evaluate(&[1, 2, 3], "*+", FORWARD), Ok(5));
+        assert_eq!(evaluate(&[8, 4, 2], "-/", FORWARD), Ok(6));
+        assert_eq!(evaluate(&[8, 4, 2], "--", FORWARD), Ok(2));
+        assert_eq!(evaluate(&[8, 4, 2], "//", FORWARD), Ok(1));
+        assert_eq!(evaluate(&[9, 3, 2, 4], "-*+", FORWARD), Ok(7));
+    }
+
+    #[test]
+    fn division_truncates_towards_zero() {
+        assert_eq!(evaluate(&[7, 2], "/", FORWARD), Ok(3));
+        assert_eq!(evaluate(&[-7, 2], "/", FORWARD), Ok(-3));
+        assert_eq!(evaluate(&[7, -2], "/", FORWARD), Ok(-3));
+        assert_eq!(evaluate(&[1, 0], "/", FORWARD), Err(ExprError::DivisionByZero));
+        assert_eq!(evaluate(&[i64::MIN, -1], "/", FORWARD), Err(ExprError::Overflow));
+    }
+
+    #[test]
+    fn parentheses() {
+        assert_eq!(evaluate(&[1, 2, 3], "(+)*", FORWARD), Ok(9));
+        assert_eq!(evaluate(&[1, 2, 3], "-(-)", FORWARD), Ok(2));
+        assert_eq!(evaluate(&[2, 1, 2, 3], "*((+)*)", FORWARD), Ok(18));
+        assert_eq!(evaluate(&[1, 2], "(+", FORWARD), Err(ExprError::UnbalancedParens));
+        assert_eq!(evaluate(&[1, 2], "+)", FORWARD), Err(ExprError::UnbalancedParens));
+        assert_eq!(evaluate(&[1, 2], ")(", FORWARD), Err(ExprError::MisplacedParen));
+    }
+
+    #[test]
+    fn reverse_mirrors_parentheses() {
+        // Read back to front, `*(+)` becomes `)+(*`, which only nests once the parentheses are mirrored to `(+)*`.
+        assert_eq!(evaluate(&[1, 2, 3], "*(+)", REVERSE), Ok(9));
+        assert_eq!(
+            evaluate(&[1, 2, 3], "*(+)", REVERSE),
+            evaluate(&[1, 2, 3], "(+)*", FORWARD)
+        );
+        assert_eq!(evaluate(&[1, 2, 3], "(+)*", REVERSE), Ok(5));
+        assert_eq!(evaluate(&[5, 3, 1], "+-", REVERSE), Ok(3));
+    }
+
+    #[test]
+    fn grouped_operands() {
+        let layout = Layout { group: 2, ..FORWARD };
+        assert_eq!(evaluate(&[1, 2, 3, 4], "+", layout), Ok(46));
+        assert_eq!(evaluate(&[1, 2, 3, 4, 5], "-", layout), Ok(-22));
+    }
+
+    #[test]
+    fn leftovers_are_ignored() {
+        assert_eq!(evaluate(&[1, 2, 3], "+", FORWARD), Ok(3));
+        assert_eq!(evaluate(&[1], "+-", FORWARD), Ok(1));
+        assert_eq!(evaluate(&[1], "+(", FORWARD), Ok(1));
+        assert_eq!(evaluate(&[1, 2], "+(", FORWARD), Err(ExprError::UnbalancedParens));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(evaluate(&[], "+", FORWARD), Err(ExprError::Empty));
+        assert_eq!(evaluate(&[1, 2], "%", FORWARD), Err(ExprError::UnknownSymbol('%')));
+        assert_eq!(evaluate(&[i64::MAX, 1], "+", FORWARD), Err(ExprError::Overflow));
+        assert_eq!(evaluate(&[i64::MIN, 1], "-", FORWARD), Err(ExprError::Overflow));
+    }
+}
@@ -1,3 +1,5 @@
+use day01::expr::{evaluate, Direction, Layout};
+
 fn main() {
     let input = include_str!("input.txt");
     let mut lines = input.lines();
@@ -5,34 +7,10 @@ fn main() {
 
     let numbers = lines.map(|x| x.parse::<i64>().unwrap()).collect::<Vec<_>>();
 
-    let mut part1 = numbers[0];
-    let mut part2 = numbers[0];
-
-    for (sign, n) in signs.bytes().zip(numbers.iter().skip(1)) {
-        match sign {
-            b'+' => part1 += n,
-            b'-' => part1 -= n,
-            _ => unreachable!(),
-        }
-    }
-
-    for (sign, n) in signs.bytes().rev().zip(numbers.iter().skip(1)) {
-        match sign {
-            b'+' => part2 += n,
-            b'-' => part2 -= n,
-            _ => unreachable!(),
-        }
-    }
-
-    let mut part3 = numbers[0] * 10 + numbers[1];
-    for (sign, n) in signs.bytes().rev().zip(numbers.chunks(2).skip(1)) {
-        let n = n[0] * 10 + n[1];
-        match sign {
-            b'+' => part3 += n,
-            b'-' => part3 -= n,
-            _ => unreachable!(),
-        }
-    }
+    let solve = |group, direction| evaluate(&numbers, signs, Layout { group, direction }).unwrap();
+    let part1 = solve(1, Direction::Forward);
+    let part2 = solve(1, Direction::Reverse);
+    let part3 = solve(2, Direction::Reverse);
 
     println!("{part1}");
     println!("{part2}");
@@ -1,13 +1,13 @@
-fn price(quality: u64) -> u64 {
-    quality.pow(3) * 69 + 510
-}
+use day02::pricing::Pricing;
+
+const BUDGET: u64 = 15_000_000_000_000;
 
 fn main() {
     let input = include_str!("input.txt");
-    let numbers = input
-        .split_once("\n\n")
-        .unwrap()
-        .1
+    let (header, numbers) = input.split_once("\n\n").unwrap();
+    let pricing = header.parse::<Pricing>().unwrap();
+    let price = |quality| pricing.checked_price(quality).unwrap();
+    let numbers = numbers
         .lines()
         .map(|line| line.parse::<u64>().unwrap())
         .collect::<Vec<_>>();
@@ -18,10 +18,7 @@ fn main() {
     let part2 = price(numbers.iter().filter(|&n| n % 2 == 0).sum());
     println!("{part2}");
 
-    let part3 = numbers
-        .iter()
-        .filter(|&&n| price(n) < 15_000_000_000_000)
-        .max()
-        .unwrap();
+    let max_quality = pricing.max_quality_under(BUDGET).unwrap();
+    let part3 = numbers.iter().filter(|&&n| n <= max_quality).max().unwrap();
     println!("{part3}");
 }
@@ -0,0 +1,204 @@
+use std::{fmt::Display, str::FromStr};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    Add(u64),
+    Multiply(u64),
+    Power(u32),
+}
+
+impl Step {
+    fn checked_apply(self, x: u64) -> Option<u64> {
+        match self {
+            Self::Add(n) => x.checked_add(n),
+            Self::Multiply(n) => x.checked_mul(n),
+            Self::Power(n) => x.checked_pow(n),
+        }
+    }
+}
+
+/// A price function, built from the `Function X: ...` header lines.
+///
+/// The functions are applied bottom-up: the last one listed is applied to the quality first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pricing {
+    steps: Vec<Step>,
+}
+
+impl Pricing {
+    /// The price of a room of the given quality, or `None` if it doesn't fit in a `u64`.
+    pub fn checked_price(&self, quality: u64) -> Option<u64> {
+        self.steps
+            .iter()
+            .rev()
+            .try_fold(quality, |x, step| step.checked_apply(x))
+    }
+
+    /// The highest quality whose price is strictly below `limit`, or `None` if even quality 0 is too expensive.
+    ///
+    /// Every step is non-decreasing, so prices are too, and a binary search finds the answer.
+    pub fn max_quality_under(&self, limit: u64) -> Option<u64> {
+        let affordable = |quality| self.checked_price(quality).is_some_and(|price| price < limit);
+        if !affordable(0) {
+            return None;
+        }
+
+        // Invariant: `lo` is affordable and everything above `hi` isn't.
+        let (mut lo, mut hi) = (0, u64::MAX);
+        while lo < hi {
+            let mid = lo + (hi - lo).div_ceil(2);
+            if affordable(mid) {
+                lo = mid;
+            } else {
+                hi = mid - 1;
+            }
+        }
+        Some(lo)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParsePricingError {
+    Line(String),
+    Operation(String),
+    Argument(std::num::ParseIntError),
+}
+
+impl Display for ParsePricingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Line(line) => write!(f, "expected `Function X: ...`, found {line:?}"),
+            Self::Operation(op) => write!(f, "unknown operation {op:?}"),
+            Self::Argument(err) => write!(f, "invalid argument: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ParsePricingError {}
+
+impl FromStr for Pricing {
+    type Err = ParsePricingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let steps = s
+            .lines()
+            .map(|line| {
+                let (_, op) = line
+                    .split_once(": ")
+                    .ok_or_else(|| ParsePricingError::Line(line.to_owned()))?;
+                let (name, arg) = op
+                    .rsplit_once(' ')
+                    .ok_or_else(|| ParsePricingError::Operation(op.to_owned()))?;
+                match name {
+                    "ADD" => Ok(Step::Add(arg.parse().map_err(ParsePricingError::Argument)?)),
+                    "MULTIPLY" => Ok(Step::Multiply(arg.parse().map_err(ParsePricingError::Argument)?)),
+                    "RAISE TO THE POWER OF" => Ok(Step::Power(arg.parse().map_err(ParsePricingError::Argument)?)),
+                    _ => Err(ParsePricingError::Operation(name.to_owned())),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { steps })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    fn pricing(header: &str) -> Pricing {
+        header.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_the_header() {
+        let pricing = pricing("Function A: ADD 510\nFunction B: MULTIPLY 69\nFunction C: RAISE TO THE POWER OF 3");
+        assert_eq!(pricing.steps, [Step::Add(510), Step::Multiply(69), Step::Power(3)]);
+        assert_eq!(pricing.checked_price(5), Some(5u64.pow(3) * 69 + 510));
+        assert_eq!(pricing.checked_price(u64::MAX), None);
+    }
+
+    #[test]
+    fn header_errors() {
+        let parse = |header: &str| header.parse::<Pricing>().unwrap_err();
+        assert_eq!(
+            parse("Function A ADD 5"),
+            ParsePricingError::Line("Function A ADD 5".to_owned())
+        );
+        assert_eq!(
+            parse("Function A: SUBTRACT 5"),
+            ParsePricingError::Operation("SUBTRACT".to_owned())
+        );
+        assert_eq!(
+            parse("Function A: NOTHING"),
+            ParsePricingError::Operation("NOTHING".to_owned())
+        );
+        for header in [
+            "Function A: ADD five",
+            "Function A: MULTIPLY -1",
+            "Function A: RAISE TO THE POWER OF 1e3",
+        ] {
+            assert!(matches!(parse(header), ParsePricingError::Argument(_)), "{header}");
+        }
+        assert!(matches!(
+            parse("Function A: ADD 18446744073709551616"),
+            ParsePricingError::Argument(_)
+        ));
+    }
+
+    #[test]
+    fn max_quality_at_the_overflow_boundary() {
+        assert_eq!(
+            pricing("Function A: ADD 0").max_quality_under(u64::MAX),
+            Some(u64::MAX - 1)
+        );
+        assert_eq!(
+            pricing("Function A: MULTIPLY 2").max_quality_under(u64::MAX),
+            Some((1 << 63) - 1)
+        );
+        assert_eq!(
+            pricing("Function A: RAISE TO THE POWER OF 2").max_quality_under(u64::MAX),
+            Some(u64::from(u32::MAX))
+        );
+        assert_eq!(
+            pricing("Function A: ADD 1\nFunction B: MULTIPLY 3").max_quality_under(u64::MAX),
+            Some(u64::MAX / 3 - 1)
+        );
+    }
+
+    #[test]
+    fn quality_zero_too_expensive() {
+        let add_100 = pricing("Function A: ADD 100");
+        assert_eq!(add_100.max_quality_under(0), None);
+        assert_eq!(add_100.max_quality_under(100), None);
+        assert_eq!(add_100.max_quality_under(101), Some(0));
+        assert_eq!(
+            pricing("Function A: ADD 18446744073709551615").max_quality_under(u64::MAX),
+            None
+        );
+    }
+
+    #[test]
+    fn max_quality_matches_a_linear_scan() {
+        let mut rng = StdRng::seed_from_u64(0x7a6d_76e9_c8b4_1f35);
+        for _ in 0..500 {
+            let steps = (0..rng.gen_range(1..=3))
+                .map(|_| match rng.gen_range(0..3) {
+                    0 => Step::Add(rng.gen_range(0..50)),
+                    1 => Step::Multiply(rng.gen_range(1..5)),
+                    _ => Step::Power(rng.gen_range(1..4)),
+                })
+                .collect();
+            let pricing = Pricing { steps };
+            let limit = rng.gen_range(0..2000);
+
+            // Every step keeps the price at least the quality, so the scan stops before `limit`.
+            let scanned = (0..)
+                .take_while(|&quality| pricing.checked_price(quality).unwrap() < limit)
+                .last();
+            assert_eq!(pricing.max_quality_under(limit), scanned, "{pricing:?} under {limit}");
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use std::ops::RangeInclusive;
+
+/// A set of integers, stored as sorted, disjoint and non-adjacent inclusive ranges.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    ranges: Vec<(u64, u64)>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build the set from already sorted ranges, merging any that overlap or touch.
+    fn from_sorted(sorted: impl IntoIterator<Item = (u64, u64)>) -> Self {
+        let mut ranges: Vec<(u64, u64)> = Vec::new();
+        for (start, end) in sorted {
+            match ranges.last_mut() {
+                Some((_, last_end)) if start <= last_end.saturating_add(1) => *last_end = (*last_end).max(end),
+                _ => ranges.push((start, end)),
+            }
+        }
+        Self { ranges }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// How many integers are in the set. This is a `u128` as the whole of `u64` doesn't fit in one.
+    pub fn len(&self) -> u128 {
+        self.ranges
+            .iter()
+            .map(|&(start, end)| u128::from(end - start) + 1)
+            .sum()
+    }
+
+    pub fn contains(&self, n: u64) -> bool {
+        let i = self.ranges.partition_point(|&(_, end)| end < n);
+        self.ranges.get(i).is_some_and(|&(start, _)| start <= n)
+    }
+
+    /// The ranges making up the set, in increasing order.
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<u64>> + '_ {
+        self.ranges.iter().map(|&(start, end)| start..=end)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        // Merge the two sorted lists by start, then let `from_sorted` join the overlaps.
+        let (mut a, mut b) = (self.ranges.iter().peekable(), other.ranges.iter().peekable());
+        let merged = std::iter::from_fn(|| match (a.peek(), b.peek()) {
+            (Some(x), Some(y)) if y.0 < x.0 => b.next(),
+            (Some(_), _) => a.next(),
+            (None, _) => b.next(),
+        });
+        Self::from_sorted(merged.copied())
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(&(s1, e1)), Some(&(s2, e2))) = (self.ranges.get(i), other.ranges.get(j)) {
+            let (start, end) = (s1.max(s2), e1.min(e2));
+            if start <= end {
+                ranges.push((start, end));
+            }
+            // Whichever range ends first can't overlap anything else in the other set.
+            if e1 < e2 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    /// The integers in `self` that aren't in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let mut j = 0;
+        for &(start, end) in &self.ranges {
+            // Skip the ranges of `other` that end before this one starts; they can't affect later ranges either.
+            while other.ranges.get(j).is_some_and(|&(_, e)| e < start) {
+                j += 1;
+            }
+
+            let mut start = Some(start);
+            let mut k = j;
+            while let (Some(s), Some(&(cut_start, cut_end))) = (start, other.ranges.get(k)) {
+                if cut_start > end {
+                    break;
+                }
+                if cut_start > s {
+                    ranges.push((s, cut_start - 1));
+                }
+                start = cut_end.checked_add(1).filter(|&next| next <= end);
+                k += 1;
+            }
+            if let Some(s) = start {
+                ranges.push((s, end));
+            }
+        }
+        Self { ranges }
+    }
+}
+
+impl FromIterator<RangeInclusive<u64>> for IntervalSet {
+    /// Collect ranges in any order, skipping empty ones.
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<u64>>>(iter: I) -> Self {
+        let mut ranges = iter
+            .into_iter()
+            .filter(|r| !r.is_empty())
+            .map(|r| r.into_inner())
+            .collect::<Vec<_>>();
+        ranges.sort_unstable();
+        Self::from_sorted(ranges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    /// How far above its base a random range can reach.
+    const SPAN: u64 = 40;
+
+    /// A few ranges within `base..=base + SPAN`, so they often overlap or touch, and some are reversed (empty).
+    fn random_ranges(rng: &mut StdRng, base: u64) -> Vec<RangeInclusive<u64>> {
+        (0..rng.gen_range(0..6))
+            .map(|_| {
+                let start = base + rng.gen_range(0..=SPAN);
+                let len = rng.gen_range(0..8);
+                if rng.gen_range(0..8) == 0 {
+                    start..=start.saturating_sub(1 + len)
+                } else {
+                    start..=start.saturating_add(len).min(base + SPAN)
+                }
+            })
+            .collect()
+    }
+
+    fn expand(ranges: impl IntoIterator<Item = RangeInclusive<u64>>) -> BTreeSet<u64> {
+        ranges.into_iter().flatten().collect()
+    }
+
+    fn assert_matches(set: &IntervalSet, model: &BTreeSet<u64>, base: u64) {
+        assert_eq!(&expand(set.ranges()), model);
+        assert_eq!(set.len(), model.len() as u128);
+        assert_eq!(set.is_empty(), model.is_empty());
+        for range in set.ranges() {
+            assert!(!range.is_empty());
+        }
+        for pair in set.ranges().collect::<Vec<_>>().windows(2) {
+            assert!(pair[1].start() - pair[0].end() > 1, "{pair:?} overlap or touch");
+        }
+        let below = base.checked_sub(1);
+        let above = base.checked_add(SPAN + 1);
+        for n in below.into_iter().chain(base..=base + SPAN).chain(above) {
+            assert_eq!(set.contains(n), model.contains(&n), "{n}");
+        }
+    }
+
+    #[test]
+    fn matches_btree_set() {
+        let mut rng = StdRng::seed_from_u64(0x3243_f6a8_885a_308d);
+        for base in [0, 1 << 40, u64::MAX - SPAN] {
+            for _ in 0..2000 {
+                let (a, b) = (random_ranges(&mut rng, base), random_ranges(&mut rng, base));
+                let (set_a, set_b) = (a.iter().cloned().collect::<IntervalSet>(), b.iter().cloned().collect());
+                let (model_a, model_b) = (expand(a), expand(b));
+
+                assert_matches(&set_a, &model_a, base);
+                assert_matches(&set_a.union(&set_b), &(&model_a | &model_b), base);
+                assert_matches(&set_a.intersection(&set_b), &(&model_a & &model_b), base);
+                assert_matches(&set_a.difference(&set_b), &(&model_a - &model_b), base);
+                assert_matches(&set_b.difference(&set_a), &(&model_b - &model_a), base);
+            }
+        }
+    }
+
+    #[test]
+    fn touching_and_extreme_ranges() {
+        let set = [1..=3, 4..=6, 8..=8].into_iter().collect::<IntervalSet>();
+        assert_eq!(set.ranges().collect::<Vec<_>>(), [1..=6, 8..=8]);
+
+        #[allow(clippy::reversed_empty_ranges)]
+        let empty = [5..=4, u64::MAX..=0].into_iter().collect::<IntervalSet>();
+        assert!(empty.is_empty());
+
+        let everything = [0..=u64::MAX].into_iter().collect::<IntervalSet>();
+        assert_eq!(everything.len(), 1 << 64);
+        assert!(everything.contains(u64::MAX));
+
+        let top = [u64::MAX..=u64::MAX].into_iter().collect::<IntervalSet>();
+        assert_eq!(
+            everything.difference(&top).ranges().collect::<Vec<_>>(),
+            [0..=u64::MAX - 1]
+        );
+        assert_eq!(top.union(&[0..=u64::MAX - 1].into_iter().collect()), everything);
+        assert!(top.difference(&everything).is_empty());
+    }
+}
@@ -0,0 +1 @@
+pub mod interval_set;
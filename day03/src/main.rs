@@ -1,8 +1,10 @@
-use std::collections::HashSet;
+use std::ops::RangeInclusive;
 
-fn parse_range(s: &str) -> (usize, usize) {
+use day03::interval_set::IntervalSet;
+
+fn parse_range(s: &str) -> RangeInclusive<u64> {
     let (start, end) = s.split_once('-').unwrap();
-    (start.parse().unwrap(), end.parse().unwrap())
+    start.parse().unwrap()..=end.parse().unwrap()
 }
 
 fn main() {
@@ -10,38 +12,17 @@ fn main() {
 
     let part1 = input
         .split_ascii_whitespace()
-        .map(|r| {
-            let (start, end) = parse_range(r);
-            (start..=end).count()
-        })
-        .sum::<usize>();
-
-    let part2 = input
-        .lines()
-        .map(|l| {
-            let (r1, r2) = l.split_once(' ').unwrap();
-            let (start1, end1) = parse_range(r1);
-            let (start2, end2) = parse_range(r2);
-
-            let common_start = start1.max(start2);
-            let common_end = end1.min(end2);
-
-            (start1..=end1).count() + (start2..=end2).count() - (common_start..=common_end).count()
-        })
-        .sum::<usize>();
+        .map(|r| IntervalSet::from_iter([parse_range(r)]).len())
+        .sum::<u128>();
 
     let piles = input
         .lines()
-        .map(|l| {
-            let (r1, r2) = l.split_once(' ').unwrap();
-            let (start1, end1) = parse_range(r1);
-            let (start2, end2) = parse_range(r2);
-
-            (start1..=end1).chain(start2..=end2).collect::<HashSet<_>>()
-        })
+        .map(|l| l.split_ascii_whitespace().map(parse_range).collect::<IntervalSet>())
         .collect::<Vec<_>>();
 
-    let part3 = piles.windows(2).map(|w| w[0].union(&w[1]).count()).max().unwrap();
+    let part2 = piles.iter().map(IntervalSet::len).sum::<u128>();
+
+    let part3 = piles.windows(2).map(|w| w[0].union(&w[1]).len()).max().unwrap();
 
     println!("{part1}");
     println!("{part2}");
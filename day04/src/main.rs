@@ -1,3 +1,5 @@
+use day04::rle::{self, CountPosition};
+
 fn memory(c: u8) -> usize {
     match c {
         b'A'..=b'Z' => usize::from(c - b'A' + 1),
@@ -6,30 +8,6 @@ fn memory(c: u8) -> usize {
     }
 }
 
-fn rle(s: &str) -> String {
-    let mut result = String::new();
-
-    let mut n = 0;
-    let mut last = b'\0';
-    for c in s.bytes() {
-        if c == last {
-            n += 1;
-        } else {
-            if n > 0 {
-                result.push_str(&format!("{n}"));
-            }
-            result.push(last as char);
-            last = c;
-            n = 1;
-        }
-    }
-    if n > 0 {
-        result.push_str(&format!("{n}"));
-        result.push(last as char);
-    }
-    result
-}
-
 fn main() {
     let input = include_str!("input.txt");
 
@@ -51,8 +29,8 @@ fn main() {
 
     let part3 = input
         .lines()
-        .map(rle)
-        .map(|line| line.bytes().map(memory).sum::<usize>())
+        .map(|line| rle::encode(line.as_bytes(), CountPosition::Before).unwrap())
+        .map(|line| line.into_iter().map(memory).sum::<usize>())
         .sum::<usize>();
     println!("{part3}");
 }
@@ -0,0 +1,268 @@
+use std::{
+    fmt::Display,
+    io::{self, BufReader, BufWriter, Read, Write},
+};
+
+/// Where each run's count goes relative to the byte being repeated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CountPosition {
+    /// `AAAB` is written `3A1B`.
+    Before,
+    /// `AAAB` is written `A3B1`.
+    After,
+}
+
+#[derive(Debug)]
+pub enum RleError {
+    Io(io::Error),
+    /// The data to encode contains an ASCII digit, which would be mistaken for part of a count.
+    DigitInData {
+        offset: u64,
+    },
+    MissingCount {
+        offset: u64,
+    },
+    MissingSymbol {
+        offset: u64,
+    },
+    ZeroCount {
+        offset: u64,
+    },
+    CountOverflow {
+        offset: u64,
+    },
+}
+
+impl Display for RleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "{err}"),
+            Self::DigitInData { offset } => write!(f, "can't encode the digit at offset {offset}"),
+            Self::MissingCount { offset } => write!(f, "expected a count at offset {offset}"),
+            Self::MissingSymbol { offset } => write!(f, "expected a symbol at offset {offset}"),
+            Self::ZeroCount { offset } => write!(f, "the run at offset {offset} is empty"),
+            Self::CountOverflow { offset } => write!(f, "the count at offset {offset} is too big"),
+        }
+    }
+}
+
+impl std::error::Error for RleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for RleError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// Run-length encode `data`, which must not contain ASCII digits, so that [`decode`] gives it back unchanged.
+pub fn encode(data: &[u8], position: CountPosition) -> Result<Vec<u8>, RleError> {
+    let mut out = Vec::new();
+    encode_stream(data, &mut out, position)?;
+    Ok(out)
+}
+
+/// Undo [`encode`].
+pub fn decode(encoded: &[u8], position: CountPosition) -> Result<Vec<u8>, RleError> {
+    let mut out = Vec::new();
+    decode_stream(encoded, &mut out, position)?;
+    Ok(out)
+}
+
+/// Like [`encode`], but reading from `reader` and writing to `writer` as it goes.
+pub fn encode_stream<R: Read, W: Write>(reader: R, writer: W, position: CountPosition) -> Result<(), RleError> {
+    let mut writer = BufWriter::new(writer);
+    // The symbol is written as a raw byte: going through `char` would turn bytes above 0x7f into two UTF-8 bytes.
+    let mut write_run = |symbol: u8, count: u64| match position {
+        CountPosition::Before => write!(writer, "{count}").and_then(|()| writer.write_all(&[symbol])),
+        CountPosition::After => writer.write_all(&[symbol]).and_then(|()| write!(writer, "{count}")),
+    };
+
+    // Each run is a symbol and how many times it's been seen so far, so there's no sentinel to mistake for data.
+    let mut run: Option<(u8, u64)> = None;
+    for (offset, c) in (0..).zip(BufReader::new(reader).bytes()) {
+        let c = c?;
+        if c.is_ascii_digit() {
+            return Err(RleError::DigitInData { offset });
+        }
+
+        match &mut run {
+            Some((symbol, count)) if *symbol == c => *count += 1,
+            _ => {
+                if let Some((symbol, count)) = run.replace((c, 1)) {
+                    write_run(symbol, count)?;
+                }
+            }
+        }
+    }
+    if let Some((symbol, count)) = run {
+        write_run(symbol, count)?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Like [`decode`], but reading from `reader` and writing to `writer` as it goes.
+pub fn decode_stream<R: Read, W: Write>(reader: R, writer: W, position: CountPosition) -> Result<(), RleError> {
+    let mut writer = BufWriter::new(writer);
+    let mut count: Option<u64> = None;
+    let mut symbol: Option<u8> = None;
+    let mut offset = 0;
+
+    for c in BufReader::new(reader).bytes() {
+        let c = c?;
+        if let Some(digit) = char::from(c).to_digit(10) {
+            if position == CountPosition::After && symbol.is_none() {
+                return Err(RleError::MissingSymbol { offset });
+            }
+            count = count
+                .unwrap_or(0)
+                .checked_mul(10)
+                .and_then(|n| n.checked_add(u64::from(digit)))
+                .map(Some)
+                .ok_or(RleError::CountOverflow { offset })?;
+        } else {
+            match position {
+                CountPosition::Before => write_run(&mut writer, c, count.take(), offset)?,
+                CountPosition::After => {
+                    if let Some(prev) = symbol {
+                        write_run(&mut writer, prev, count.take(), offset)?;
+                    }
+                    symbol = Some(c);
+                }
+            }
+        }
+        offset += 1;
+    }
+
+    match (position, symbol) {
+        (CountPosition::Before, _) if count.is_some() => return Err(RleError::MissingSymbol { offset }),
+        (CountPosition::After, Some(prev)) => write_run(&mut writer, prev, count, offset)?,
+        _ => {}
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+fn write_run<W: Write>(writer: &mut W, symbol: u8, count: Option<u64>, offset: u64) -> Result<(), RleError> {
+    let mut left = match count {
+        None => return Err(RleError::MissingCount { offset }),
+        Some(0) => return Err(RleError::ZeroCount { offset }),
+        Some(count) => count,
+    };
+
+    // Long runs are written a chunk at a time rather than being built up in memory.
+    let chunk = [symbol; 4096];
+    while left > 0 {
+        let n = left.min(chunk.len() as u64);
+        writer.write_all(&chunk[..n as usize])?;
+        left -= n;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    /// Random bytes other than ASCII digits, in runs of random length.
+    fn random_data(rng: &mut StdRng, alphabet: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        for _ in 0..rng.gen_range(0..40) {
+            let symbol = alphabet[rng.gen_range(0..alphabet.len())];
+            let longest = if rng.gen_range(0..10) == 0 { 300 } else { 5 };
+            let run = rng.gen_range(1..=longest);
+            data.extend(std::iter::repeat_n(symbol, run as usize));
+        }
+        data
+    }
+
+    const POSITIONS: [CountPosition; 2] = [CountPosition::Before, CountPosition::After];
+
+    #[test]
+    fn round_trips_random_bytes() {
+        let every_byte = (0..=u8::MAX).filter(|b| !b.is_ascii_digit()).collect::<Vec<_>>();
+        let few_bytes = [b'\0', b'A', 0xC3, 0xFF];
+        let mut rng = StdRng::seed_from_u64(0xa409_3822_299f_31d0);
+        for _ in 0..5000 {
+            let alphabet: &[u8] = if rng.gen_range(0..2) == 0 {
+                &every_byte
+            } else {
+                &few_bytes
+            };
+            let data = random_data(&mut rng, alphabet);
+            for position in POSITIONS {
+                let encoded = encode(&data, position).unwrap();
+                assert_eq!(decode(&encoded, position).unwrap(), data, "{position:?} {data:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn streams_match_slices() {
+        let mut rng = StdRng::seed_from_u64(0x082e_fa98_ec4e_6c89);
+        for _ in 0..200 {
+            let data = random_data(&mut rng, b"ab\n\0\x80");
+            for position in POSITIONS {
+                let mut encoded = Vec::new();
+                encode_stream(data.as_slice(), &mut encoded, position).unwrap();
+                assert_eq!(encoded, encode(&data, position).unwrap());
+
+                let mut decoded = Vec::new();
+                decode_stream(encoded.as_slice(), &mut decoded, position).unwrap();
+                assert_eq!(decoded, data);
+            }
+        }
+    }
+
+    #[test]
+    fn formats() {
+        assert_eq!(encode(b"AAAB", CountPosition::Before).unwrap(), b"3A1B");
+        assert_eq!(encode(b"AAAB", CountPosition::After).unwrap(), b"A3B1");
+        assert_eq!(encode(b"\0\0A", CountPosition::Before).unwrap(), b"2\x001A");
+        assert_eq!(
+            encode(&[0xC3, 0xC3, b'A'], CountPosition::Before).unwrap(),
+            [b'2', 0xC3, b'1', b'A']
+        );
+        assert_eq!(encode(b"", CountPosition::Before).unwrap(), b"");
+        assert_eq!(decode(b"12x", CountPosition::Before).unwrap(), [b'x'; 12]);
+    }
+
+    #[test]
+    fn errors() {
+        use CountPosition::{After, Before};
+
+        assert!(matches!(
+            encode(b"A1", Before),
+            Err(RleError::DigitInData { offset: 1 })
+        ));
+        assert!(matches!(
+            decode(b"A", Before),
+            Err(RleError::MissingCount { offset: 0 })
+        ));
+        assert!(matches!(
+            decode(b"3", Before),
+            Err(RleError::MissingSymbol { offset: 1 })
+        ));
+        assert!(matches!(decode(b"0A", Before), Err(RleError::ZeroCount { .. })));
+        assert!(matches!(decode(b"A", After), Err(RleError::MissingCount { .. })));
+        assert!(matches!(
+            decode(b"3A", After),
+            Err(RleError::MissingSymbol { offset: 0 })
+        ));
+        assert!(matches!(
+            decode(b"99999999999999999999A", Before),
+            Err(RleError::CountOverflow { .. })
+        ));
+    }
+}
@@ -0,0 +1,132 @@
+use crate::metric::{Metric, Point};
+
+/// A k-d tree over a fixed set of points that supports removing them, for repeated nearest-neighbour queries.
+///
+/// The tree is stored implicitly: the node for `points[lo..hi]` sits at the midpoint, with its left subtree before
+/// it and its right subtree after it.
+#[derive(Debug, Clone)]
+pub struct KdTree<const D: usize> {
+    points: Vec<Point<D>>,
+    /// How many points are still in the subtree rooted at each node.
+    alive: Vec<usize>,
+    removed: Vec<bool>,
+    /// The bounding box of each subtree, as its lowest and highest corner.
+    bounds: Vec<(Point<D>, Point<D>)>,
+}
+
+impl<const D: usize> KdTree<D> {
+    pub fn new(mut points: Vec<Point<D>>) -> Self {
+        let n = points.len();
+        build(&mut points, 0);
+
+        let mut tree = Self {
+            alive: vec![0; n],
+            removed: vec![false; n],
+            bounds: vec![([0; D], [0; D]); n],
+            points,
+        };
+        tree.fill(0, n);
+        tree
+    }
+
+    fn fill(&mut self, lo: usize, hi: usize) {
+        if lo == hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        self.fill(lo, mid);
+        self.fill(mid + 1, hi);
+
+        let ps = &self.points[lo..hi];
+        let min = std::array::from_fn(|axis| ps.iter().map(|p| p[axis]).min().unwrap());
+        let max = std::array::from_fn(|axis| ps.iter().map(|p| p[axis]).max().unwrap());
+        self.bounds[mid] = (min, max);
+        self.alive[mid] = hi - lo;
+    }
+
+    pub fn len(&self) -> usize {
+        self.alive.get(self.points.len() / 2).copied().unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove and return the point nearest to `from`, breaking ties by the lowest coordinates, x first.
+    pub fn pop_nearest<M: Metric>(&mut self, metric: M, from: Point<D>) -> Option<Point<D>> {
+        let mut best = None;
+        self.nearest(metric, from, 0, self.points.len(), 0, &mut best);
+        let (_, _, at) = best?;
+        self.remove(at);
+        Some(self.points[at])
+    }
+
+    fn nearest<M: Metric>(
+        &self,
+        metric: M,
+        from: Point<D>,
+        lo: usize,
+        hi: usize,
+        depth: usize,
+        best: &mut Option<(u64, Point<D>, usize)>,
+    ) {
+        if lo == hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        if self.alive[mid] == 0 {
+            return;
+        }
+
+        // A subtree can only hold a tie at the best distance so far, so prune strictly.
+        let (min, max) = self.bounds[mid];
+        let closest_corner = std::array::from_fn(|axis| from[axis].clamp(min[axis], max[axis]));
+        if best.is_some_and(|(d, _, _)| metric.dist(&from, &closest_corner) > d) {
+            return;
+        }
+
+        let p = self.points[mid];
+        if !self.removed[mid] {
+            let candidate = (metric.dist(&from, &p), p, mid);
+            if best.is_none_or(|(d, q, _)| (candidate.0, candidate.1) < (d, q)) {
+                *best = Some(candidate);
+            }
+        }
+
+        let axis = depth % D;
+        let (first, second) = if from[axis] < p[axis] {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+        self.nearest(metric, from, first.0, first.1, depth + 1, best);
+        self.nearest(metric, from, second.0, second.1, depth + 1, best);
+    }
+
+    fn remove(&mut self, at: usize) {
+        self.removed[at] = true;
+        let (mut lo, mut hi) = (0, self.points.len());
+        loop {
+            let mid = lo + (hi - lo) / 2;
+            self.alive[mid] -= 1;
+            match at.cmp(&mid) {
+                std::cmp::Ordering::Less => hi = mid,
+                std::cmp::Ordering::Equal => break,
+                std::cmp::Ordering::Greater => lo = mid + 1,
+            }
+        }
+    }
+}
+
+/// Arrange `points` so each midpoint splits its range on the axis for its depth, cycling through the axes.
+fn build<const D: usize>(points: &mut [Point<D>], depth: usize) {
+    if points.len() <= 1 {
+        return;
+    }
+    let mid = points.len() / 2;
+    let axis = depth % D;
+    points.select_nth_unstable_by_key(mid, |p| p[axis]);
+    let (left, right) = points.split_at_mut(mid);
+    build(left, depth + 1);
+    build(&mut right[1..], depth + 1);
+}
@@ -1,48 +1,65 @@
 use itertools::Itertools;
 
-type Point = (i64, i64);
+use day05::{
+    metric::{Chebyshev, Manhattan, Metric, Point, SquaredEuclidean},
+    tour::{Improvement, Tour},
+};
 
-fn parse_tuple(s: &str) -> Point {
+fn parse_tuple<const D: usize>(s: &str) -> Point<D> {
     s[1..s.len() - 1]
-        .split_once(", ")
-        .map(|(a, b)| (a.parse().unwrap(), b.parse().unwrap()))
+        .split(", ")
+        .map(|n| n.parse().unwrap())
+        .collect_vec()
+        .try_into()
         .unwrap()
 }
 
-fn dist(a: Point, b: Point) -> u64 {
-    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
-}
-
-fn main() {
-    let input = include_str!("input.txt");
+fn solve<M: Metric, const D: usize>(input: &str, metric: M) {
+    let origin = [0; D];
+    let islands = input.lines().map(parse_tuple::<D>).collect_vec();
+    let dist = |a: &Point<D>, b: &Point<D>| metric.dist(a, b);
 
-    let (closest, furthest) = input
-        .lines()
-        .map(parse_tuple)
-        .map(|p| dist((0, 0), p))
-        .minmax()
-        .into_option()
-        .unwrap();
+    let (closest, furthest) = islands.iter().map(|p| dist(&origin, p)).minmax().into_option().unwrap();
     let part1 = furthest - closest;
     println!("{part1}");
 
-    let closest = input.lines().map(parse_tuple).min_by_key(|&p| dist((0, 0), p)).unwrap();
-    let part2 = input
-        .lines()
-        .map(parse_tuple)
+    let closest = islands.iter().min_by_key(|p| dist(&origin, p)).unwrap();
+    let part2 = islands
+        .iter()
         .filter(|&p| p != closest)
         .map(|p| dist(closest, p))
         .min()
         .unwrap();
     println!("{part2}");
 
-    let mut island = (0, 0);
-    let mut islands = input.lines().map(parse_tuple).collect_vec();
+    let tour = Tour::greedy(metric, origin, islands);
+    let part3 = tour.length();
+    println!("{part3}");
 
-    let mut part3 = 0;
-    while let Some(next) = islands.iter().position_min_by_key(|&p| dist(island, *p)) {
-        part3 += dist(island, islands[next]);
-        island = islands.remove(next);
+    // `cargo run -p day05 -- --two-opt` to see how much shorter the tour could be.
+    if std::env::args().any(|arg| arg == "--two-opt") {
+        println!("{}", Improvement::new(&tour, &tour.two_opt()));
+    }
+}
+
+fn solve_in<M: Metric>(input: &str, metric: M) {
+    // Islands can be given in 2 or 3 dimensions.
+    match input.lines().next().unwrap().matches(", ").count() + 1 {
+        2 => solve::<M, 2>(input, metric),
+        3 => solve::<M, 3>(input, metric),
+        d => panic!("unsupported number of dimensions: {d}"),
+    }
+}
+
+fn main() {
+    let input = include_str!("input.txt");
+
+    // `cargo run -p day05 -- --chebyshev` or `-- --euclidean` to measure distances differently.
+    if std::env::args().any(|arg| arg == "--chebyshev") {
+        solve_in(input, Chebyshev);
+    } else if std::env::args().any(|arg| arg == "--euclidean") {
+        solve_in(input, SquaredEuclidean);
+    } else {
+        solve_in(input, Manhattan);
     }
-    println!("{part3}");
 }
@@ -0,0 +1,45 @@
+/// A point with `D` integer coordinates.
+pub type Point<const D: usize> = [i64; D];
+
+/// A way of measuring the distance between two points.
+///
+/// Distances must only depend on how far apart the points are along each axis, and never shrink when one of those
+/// gaps grows, which is what lets [`crate::kd_tree::KdTree`] rule out whole boxes of points at once.
+pub trait Metric: Copy {
+    fn dist<const D: usize>(&self, a: &Point<D>, b: &Point<D>) -> u64;
+}
+
+fn gaps<'a, const D: usize>(a: &'a Point<D>, b: &'a Point<D>) -> impl Iterator<Item = u64> + 'a {
+    a.iter().zip(b).map(|(x, y)| x.abs_diff(*y))
+}
+
+/// Moving along one axis at a time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Manhattan;
+
+impl Metric for Manhattan {
+    fn dist<const D: usize>(&self, a: &Point<D>, b: &Point<D>) -> u64 {
+        gaps(a, b).sum()
+    }
+}
+
+/// Moving along every axis at once, like a king in chess.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Chebyshev;
+
+impl Metric for Chebyshev {
+    fn dist<const D: usize>(&self, a: &Point<D>, b: &Point<D>) -> u64 {
+        gaps(a, b).max().unwrap_or(0)
+    }
+}
+
+/// The square of the straight-line distance, which keeps everything in integers and orders points the same way.
+/// Saturates at `u64::MAX` for points too far apart.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SquaredEuclidean;
+
+impl Metric for SquaredEuclidean {
+    fn dist<const D: usize>(&self, a: &Point<D>, b: &Point<D>) -> u64 {
+        gaps(a, b).fold(0u64, |acc, gap| acc.saturating_add(gap.saturating_mul(gap)))
+    }
+}
@@ -0,0 +1,168 @@
+use std::fmt::Display;
+
+use crate::{
+    kd_tree::KdTree,
+    metric::{Metric, Point},
+};
+
+/// A path from `start` through every point, without coming back, measured with `M`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tour<M, const D: usize> {
+    pub metric: M,
+    pub start: Point<D>,
+    pub stops: Vec<Point<D>>,
+}
+
+impl<M: Metric, const D: usize> Tour<M, D> {
+    /// Always fly to the nearest island not yet visited, breaking ties by the lowest coordinates, x first.
+    pub fn greedy(metric: M, start: Point<D>, points: Vec<Point<D>>) -> Self {
+        let mut tree = KdTree::new(points);
+        let mut stops = Vec::with_capacity(tree.len());
+        let mut here = start;
+        while let Some(next) = tree.pop_nearest(metric, here) {
+            stops.push(next);
+            here = next;
+        }
+        Self { metric, start, stops }
+    }
+
+    fn dist(&self, a: Point<D>, b: Point<D>) -> u64 {
+        self.metric.dist(&a, &b)
+    }
+
+    pub fn length(&self) -> u64 {
+        std::iter::once(&self.start)
+            .chain(&self.stops)
+            .zip(&self.stops)
+            .map(|(&a, &b)| self.dist(a, b))
+            .sum()
+    }
+
+    /// Improve the tour by reversing stretches of it for as long as that makes it shorter.
+    pub fn two_opt(&self) -> Self {
+        let mut stops = self.stops.clone();
+        let at = |stops: &[Point<D>], i: usize| if i == 0 { self.start } else { stops[i - 1] };
+
+        let mut improved = true;
+        while improved {
+            improved = false;
+            for i in 1..=stops.len() {
+                for j in i + 1..=stops.len() {
+                    // Reversing `stops[i - 1..j]` swaps which ends of it the neighbouring stops connect to.
+                    let (before, first, last) = (at(&stops, i - 1), stops[i - 1], stops[j - 1]);
+                    let after = stops.get(j).copied();
+                    let old = self.dist(before, first) + after.map_or(0, |a| self.dist(last, a));
+                    let new = self.dist(before, last) + after.map_or(0, |a| self.dist(first, a));
+                    if new < old {
+                        stops[i - 1..j].reverse();
+                        improved = true;
+                    }
+                }
+            }
+        }
+
+        Self {
+            metric: self.metric,
+            start: self.start,
+            stops,
+        }
+    }
+}
+
+/// How much [`Tour::two_opt`] shortened a greedy tour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Improvement {
+    pub greedy: u64,
+    pub improved: u64,
+}
+
+impl Improvement {
+    pub fn new<M: Metric, const D: usize>(greedy: &Tour<M, D>, improved: &Tour<M, D>) -> Self {
+        Self {
+            greedy: greedy.length(),
+            improved: improved.length(),
+        }
+    }
+}
+
+impl Display for Improvement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // 2-opt never makes a tour longer, but an `Improvement` can be built from any two tours.
+        let saved = self.greedy.abs_diff(self.improved);
+        let direction = if self.greedy >= self.improved {
+            "longer"
+        } else {
+            "shorter"
+        };
+        let percent = if self.improved == 0 {
+            0.0
+        } else {
+            100.0 * saved as f64 / self.improved as f64
+        };
+        write!(
+            f,
+            "greedy: {}, 2-opt: {}, greedy is {saved} ({percent:.2}%) {direction}",
+            self.greedy, self.improved
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::metric::{Chebyshev, Manhattan, SquaredEuclidean};
+
+    /// The greedy tour the slow way: scan every remaining point for the nearest one, lowest coordinates first.
+    fn naive_greedy<M: Metric, const D: usize>(metric: M, start: Point<D>, mut points: Vec<Point<D>>) -> Vec<Point<D>> {
+        let mut stops = Vec::new();
+        let mut here = start;
+        while let Some(i) = (0..points.len()).min_by_key(|&i| (metric.dist(&here, &points[i]), points[i])) {
+            here = points.swap_remove(i);
+            stops.push(here);
+        }
+        stops
+    }
+
+    /// Points on a small grid, so there are lots of ties and duplicates.
+    fn check_greedy<M: Metric, const D: usize>(rng: &mut StdRng, metric: M) {
+        for _ in 0..300 {
+            let n = rng.gen_range(0..40);
+            let mut point = || std::array::from_fn(|_| rng.gen_range(-3..=3));
+            let start = point();
+            let points = (0..n).map(|_| point()).collect::<Vec<Point<D>>>();
+            let tour = Tour::greedy(metric, start, points.clone());
+            assert_eq!(tour.stops, naive_greedy(metric, start, points));
+        }
+    }
+
+    #[test]
+    fn greedy_matches_naive_scan() {
+        let mut rng = StdRng::seed_from_u64(0x9216_d5d9_8979_fb1b);
+        check_greedy::<_, 2>(&mut rng, Manhattan);
+        check_greedy::<_, 2>(&mut rng, Chebyshev);
+        check_greedy::<_, 2>(&mut rng, SquaredEuclidean);
+        check_greedy::<_, 3>(&mut rng, Manhattan);
+    }
+
+    #[test]
+    fn improvement_in_either_direction() {
+        let shorter = Improvement {
+            greedy: 120,
+            improved: 100,
+        };
+        assert_eq!(
+            shorter.to_string(),
+            "greedy: 120, 2-opt: 100, greedy is 20 (20.00%) longer"
+        );
+        let longer = Improvement {
+            greedy: 100,
+            improved: 125,
+        };
+        assert_eq!(
+            longer.to_string(),
+            "greedy: 100, 2-opt: 125, greedy is 25 (20.00%) shorter"
+        );
+    }
+}
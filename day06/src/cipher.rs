@@ -0,0 +1,283 @@
+use std::{fmt::Display, ops::RangeInclusive};
+
+/// The puzzle's letters, worth 1 to 52 in this order.
+pub const PUZZLE_ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// How a corrupted symbol's value follows from the value before it: `multiplier * prev + offset`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Recurrence {
+    pub multiplier: i64,
+    pub offset: i64,
+    /// The value before the first symbol.
+    pub initial: i64,
+}
+
+/// Recovers corrupted strings: every symbol outside the alphabet takes the value the recurrence gives for the one
+/// before it, wrapped into a range of values.
+#[derive(Clone, Debug)]
+pub struct Cipher {
+    alphabet: Vec<u8>,
+    values: [Option<u8>; 256],
+    recurrence: Recurrence,
+    wrap: RangeInclusive<i64>,
+}
+
+impl Cipher {
+    /// Build a cipher whose alphabet letters are worth 1, 2, 3, ... in order.
+    ///
+    /// `wrap` must fit inside the alphabet, so every recovered value is a letter again.
+    pub fn new(alphabet: &[u8], recurrence: Recurrence, wrap: RangeInclusive<i64>) -> Result<Self, CipherError> {
+        if alphabet.len() > 255 {
+            return Err(CipherError::AlphabetSize(alphabet.len()));
+        }
+        let mut values = [None; 256];
+        for (value, &letter) in (1..).zip(alphabet) {
+            if !letter.is_ascii() {
+                return Err(CipherError::NotAscii(letter));
+            }
+            if values[usize::from(letter)].replace(value).is_some() {
+                return Err(CipherError::Duplicate(char::from(letter)));
+            }
+        }
+        if wrap.is_empty() || *wrap.start() < 1 || *wrap.end() > alphabet.len() as i64 {
+            return Err(CipherError::Wrap(wrap));
+        }
+
+        Ok(Self {
+            alphabet: alphabet.to_vec(),
+            values,
+            recurrence,
+            wrap,
+        })
+    }
+
+    /// Recover with `2 * prev - 5`, wrapped into `1..=52`.
+    pub fn puzzle() -> Self {
+        let recurrence = Recurrence {
+            multiplier: 2,
+            offset: -5,
+            initial: 0,
+        };
+        Self::new(PUZZLE_ALPHABET, recurrence, 1..=52).unwrap()
+    }
+
+    /// The value of a letter, or `None` if it's a corrupted symbol.
+    pub fn value(&self, b: u8) -> Option<u64> {
+        self.values[usize::from(b)].map(u64::from)
+    }
+
+    fn letter(&self, value: i64) -> u8 {
+        self.alphabet[value as usize - 1]
+    }
+
+    /// The value a corrupted symbol after `prev` recovers to.
+    fn next(&self, prev: i64) -> i64 {
+        let Recurrence { multiplier, offset, .. } = self.recurrence;
+        let (lo, hi) = (i128::from(*self.wrap.start()), i128::from(*self.wrap.end()));
+        let raw = i128::from(multiplier) * i128::from(prev) + i128::from(offset);
+        ((raw - lo).rem_euclid(hi - lo + 1) + lo) as i64
+    }
+
+    /// Replace every corrupted symbol with the letter it stands for.
+    pub fn decode(&self, corrupted: &str) -> String {
+        corrupted
+            .bytes()
+            .scan(self.recurrence.initial, |prev, b| {
+                *prev = match self.value(b) {
+                    Some(value) => value as i64,
+                    None => self.next(*prev),
+                };
+                Some(char::from(self.letter(*prev)))
+            })
+            .collect()
+    }
+
+    /// The inverse of [`Cipher::decode`]: replace the letters at the positions picked by `corrupt_at` with `symbol`.
+    ///
+    /// Fails if a picked letter isn't the one decoding would bring back, so decoding the result always gives `clean`.
+    pub fn encode(
+        &self,
+        clean: &str,
+        mut corrupt_at: impl FnMut(usize) -> bool,
+        symbol: u8,
+    ) -> Result<String, CipherError> {
+        if self.value(symbol).is_some() || !symbol.is_ascii() {
+            return Err(CipherError::Symbol(char::from(symbol)));
+        }
+
+        let mut prev = self.recurrence.initial;
+        clean
+            .bytes()
+            .enumerate()
+            .map(|(position, b)| {
+                let value = self.value(b).ok_or(CipherError::NotALetter {
+                    letter: char::from(b),
+                    position,
+                })? as i64;
+                let recovered = self.next(prev);
+                prev = value;
+
+                if !corrupt_at(position) {
+                    Ok(char::from(b))
+                } else if recovered == value {
+                    Ok(char::from(symbol))
+                } else {
+                    Err(CipherError::Unrecoverable { position })
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CipherError {
+    AlphabetSize(usize),
+    NotAscii(u8),
+    Duplicate(char),
+    Wrap(RangeInclusive<i64>),
+    Symbol(char),
+    NotALetter { letter: char, position: usize },
+    Unrecoverable { position: usize },
+}
+
+impl Display for CipherError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AlphabetSize(size) => write!(f, "an alphabet can have at most 255 letters, not {size}"),
+            Self::NotAscii(letter) => write!(f, "letter {letter:#04x} isn't ASCII"),
+            Self::Duplicate(letter) => write!(f, "letter {letter:?} appears more than once"),
+            Self::Wrap(wrap) => write!(f, "wrap range {wrap:?} doesn't fit in the alphabet"),
+            Self::Symbol(symbol) => write!(f, "{symbol:?} can't be used as a corrupted symbol"),
+            Self::NotALetter { letter, position } => write!(f, "{letter:?} at {position} isn't a letter"),
+            Self::Unrecoverable { position } => {
+                write!(f, "the letter at {position} wouldn't be recovered if it were corrupted")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CipherError {}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    /// Decode a random string with corrupted symbols, then corrupt the same positions again, and check decoding that
+    /// gives the clean string back.
+    fn check_round_trips(cipher: &Cipher, alphabet: &[u8], rng: &mut StdRng) {
+        for _ in 0..500 {
+            let corrupted = (0..rng.gen_range(0..60))
+                .map(|_| match rng.gen_range(0..3) {
+                    0 => '?',
+                    _ => char::from(alphabet[rng.gen_range(0..alphabet.len())]),
+                })
+                .collect::<String>();
+            let clean = cipher.decode(&corrupted);
+            assert!(clean.bytes().all(|b| cipher.value(b).is_some()));
+
+            let was_corrupted = |i| corrupted.as_bytes()[i] == b'?';
+            let encoded = cipher.encode(&clean, was_corrupted, b'?').unwrap();
+            assert_eq!(encoded, corrupted);
+            assert_eq!(cipher.decode(&encoded), clean);
+
+            // Corrupting everything that decoding would bring back also round-trips.
+            let mut prev = cipher.recurrence.initial;
+            let recoverable = clean
+                .bytes()
+                .map(|b| {
+                    let next = cipher.next(prev);
+                    prev = cipher.value(b).unwrap() as i64;
+                    next == prev
+                })
+                .collect::<Vec<_>>();
+            let encoded = cipher.encode(&clean, |i| recoverable[i], b'#').unwrap();
+            assert_eq!(cipher.decode(&encoded), clean);
+        }
+    }
+
+    #[test]
+    fn puzzle_round_trips() {
+        check_round_trips(
+            &Cipher::puzzle(),
+            PUZZLE_ALPHABET,
+            &mut StdRng::seed_from_u64(0x4528_21e6_38d0_1377),
+        );
+    }
+
+    #[test]
+    fn custom_round_trips() {
+        let recurrence = Recurrence {
+            multiplier: 3,
+            offset: 1,
+            initial: 2,
+        };
+        let cipher = Cipher::new(b"xyzw", recurrence, 2..=3).unwrap();
+        assert_eq!(cipher.decode("x??z?"), "xyzzy");
+        check_round_trips(&cipher, b"xyzw", &mut StdRng::seed_from_u64(0xbe54_66cf_34e9_0c6c));
+
+        let recurrence = Recurrence {
+            multiplier: -7,
+            offset: 100,
+            initial: 0,
+        };
+        let cipher = Cipher::new(b"ABCDEFGHIJ", recurrence, 3..=9).unwrap();
+        check_round_trips(
+            &cipher,
+            b"ABCDEFGHIJ",
+            &mut StdRng::seed_from_u64(0xc0ac_29b7_c97c_50dd),
+        );
+    }
+
+    #[test]
+    fn puzzle_recurrence() {
+        let cipher = Cipher::puzzle();
+        // `2 * 0 - 5` wraps around to 47, which is `U`.
+        assert_eq!(cipher.decode("?"), "U");
+        // `2 * 26 - 5` is 47 too, and `2 * 47 - 5 = 89` wraps to 37, which is `K`.
+        assert_eq!(cipher.decode("z??"), "zUK");
+    }
+
+    #[test]
+    fn unrecoverable_letters_are_rejected() {
+        let cipher = Cipher::puzzle();
+        assert_eq!(
+            cipher.encode("za", |i| i == 1, b'?'),
+            Err(CipherError::Unrecoverable { position: 1 })
+        );
+        assert_eq!(
+            cipher.encode("a", |_| true, b'?'),
+            Err(CipherError::Unrecoverable { position: 0 })
+        );
+        assert_eq!(cipher.encode("zU", |i| i == 1, b'?'), Ok("z?".to_owned()));
+    }
+
+    #[test]
+    fn invalid_specs_and_symbols() {
+        let recurrence = Recurrence {
+            multiplier: 1,
+            offset: 0,
+            initial: 0,
+        };
+        assert_eq!(
+            Cipher::new(b"ab", recurrence, 1..=3).unwrap_err(),
+            CipherError::Wrap(1..=3)
+        );
+        assert_eq!(
+            Cipher::new(b"aba", recurrence, 1..=2).unwrap_err(),
+            CipherError::Duplicate('a')
+        );
+        assert_eq!(
+            Cipher::puzzle().encode("a", |_| false, b'a'),
+            Err(CipherError::Symbol('a'))
+        );
+        assert_eq!(
+            Cipher::puzzle().encode("a?", |_| false, b'#'),
+            Err(CipherError::NotALetter {
+                letter: '?',
+                position: 1
+            })
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use itertools::Itertools;
 
+use day07::permutation::{Instruction, Permutation};
+
 fn main() {
     let (tracks, swaps, test_index) = include_str!("input.txt").split("\n\n").collect_tuple().unwrap();
 
@@ -14,43 +16,32 @@ fn main() {
         })
         .collect_vec();
 
-    let mut part1_tracks = tracks.clone();
-    for swap in swaps.chunks_exact(2) {
-        part1_tracks.swap(swap[0], swap[1]);
-    }
-    let part1 = part1_tracks[test_index - 1];
-    println!("{part1}");
-
-    let mut part2_tracks = tracks.clone();
-    for i in (0..swaps.len()).step_by(2) {
-        let x = swaps[i];
-        let y = swaps[i + 1];
-        let z = swaps[(i + 2) % swaps.len()];
-
-        let [x_val, y_val, z_val] = [part2_tracks[x], part2_tracks[y], part2_tracks[z]];
-        part2_tracks[x] = z_val;
-        part2_tracks[y] = x_val;
-        part2_tracks[z] = y_val;
+    let programs = [
+        swaps
+            .chunks_exact(2)
+            .map(|s| Instruction::Swap(s[0], s[1]))
+            .collect_vec(),
+        (0..swaps.len())
+            .step_by(2)
+            .map(|i| Instruction::Rotate(swaps[i], swaps[i + 1], swaps[(i + 2) % swaps.len()]))
+            .collect_vec(),
+        swaps
+            .chunks_exact(2)
+            .map(|s| Instruction::BlockSwap(s[0], s[1]))
+            .collect_vec(),
+    ];
+    let permutations = programs.map(|program| Permutation::compile(tracks.len(), &program).unwrap());
+
+    for permutation in &permutations {
+        let part = tracks[permutation.source_of(test_index - 1)];
+        println!("{part}");
     }
 
-    let part2 = part2_tracks[test_index - 1];
-    println!("{part2}");
-
-    let mut part3_tracks = tracks.clone();
-    for swap in swaps.chunks_exact(2) {
-        let &[x, y] = swap else {
-            unreachable!();
-        };
-        let [x, y] = [x.min(y), x.max(y)];
-
-        let l = std::cmp::min(part3_tracks.len() - y, y - x);
-        let block1 = x..x + l;
-        let block2 = y..y + l;
-
-        block1.zip(block2).for_each(|(i, j)| {
-            part3_tracks.swap(i, j);
-        });
+    // `cargo run -p day07 -- --repeat 1000000000000` to see where each program leaves the tracks after that many runs.
+    let repeat = std::env::args().skip_while(|arg| arg != "--repeat").nth(1);
+    if let Some(k) = repeat.map(|k| k.parse::<u64>().unwrap()) {
+        for permutation in &permutations {
+            println!("{}", tracks[permutation.pow(k).source_of(test_index - 1)]);
+        }
     }
-    let part3 = part3_tracks[test_index - 1];
-    println!("{part3}");
 }
@@ -0,0 +1,290 @@
+use std::fmt::Display;
+
+/// One step of a swap program, with 0-based indices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Instruction {
+    /// Swap the tracks at two indices.
+    Swap(usize, usize),
+    /// Move the track at `x` to `y`, the one at `y` to `z` and the one at `z` to `x`.
+    ///
+    /// With `x == z` this swaps `x` and `y`, and with all three equal it does nothing.
+    Rotate(usize, usize, usize),
+    /// Swap the two longest equal-length blocks starting at both indices that don't overlap nor run off the end.
+    BlockSwap(usize, usize),
+}
+
+impl Instruction {
+    /// Check the instruction only moves tracks around among `len` of them, without losing or copying any.
+    fn check(self, len: usize) -> Result<(), ProgramError> {
+        let indices = match self {
+            Self::Swap(x, y) | Self::BlockSwap(x, y) => vec![x, y],
+            Self::Rotate(x, y, z) => {
+                // Writing `x`, `y` and `z` in turn, `x == z` is a swap, but otherwise a repeated index overwrites a
+                // track before it has moved.
+                if (x == y || y == z) && x != z {
+                    return Err(ProgramError::RepeatedIndex(self));
+                }
+                vec![x, y, z]
+            }
+        };
+        match indices.into_iter().find(|&i| i >= len) {
+            Some(index) => Err(ProgramError::OutOfRange { index, len }),
+            None => Ok(()),
+        }
+    }
+
+    pub fn apply<T: Copy>(self, items: &mut [T]) {
+        match self {
+            Self::Swap(x, y) => items.swap(x, y),
+            Self::Rotate(x, y, z) => {
+                let [x_val, y_val, z_val] = [items[x], items[y], items[z]];
+                items[x] = z_val;
+                items[y] = x_val;
+                items[z] = y_val;
+            }
+            Self::BlockSwap(x, y) => {
+                let [x, y] = [x.min(y), x.max(y)];
+                let l = std::cmp::min(items.len() - y, y - x);
+                (x..x + l).zip(y..y + l).for_each(|(i, j)| items.swap(i, j));
+            }
+        }
+    }
+}
+
+/// A rearrangement of `len` tracks, stored as which original index ends up at each index.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Permutation {
+    source: Vec<usize>,
+}
+
+impl Permutation {
+    pub fn identity(len: usize) -> Self {
+        Self {
+            source: (0..len).collect(),
+        }
+    }
+
+    /// The rearrangement made by running `program` on `len` tracks.
+    pub fn compile(len: usize, program: &[Instruction]) -> Result<Self, ProgramError> {
+        let mut permutation = Self::identity(len);
+        for instruction in program {
+            instruction.check(len)?;
+            instruction.apply(&mut permutation.source);
+        }
+        Ok(permutation)
+    }
+
+    pub fn len(&self) -> usize {
+        self.source.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.source.is_empty()
+    }
+
+    /// The original index of the track that ends up at `index`.
+    pub fn source_of(&self, index: usize) -> usize {
+        self.source[index]
+    }
+
+    /// Rearrange `items` into a new vector.
+    pub fn apply<T: Clone>(&self, items: &[T]) -> Vec<T> {
+        assert_eq!(
+            items.len(),
+            self.len(),
+            "permutation applied to the wrong number of items"
+        );
+        self.source.iter().map(|&i| items[i].clone()).collect()
+    }
+
+    /// The rearrangement made by applying `self` and then `other`.
+    pub fn then(&self, other: &Self) -> Self {
+        assert_eq!(self.len(), other.len(), "composed permutations of different lengths");
+        Self {
+            source: other.source.iter().map(|&i| self.source[i]).collect(),
+        }
+    }
+
+    /// The rearrangement that puts every track back where it came from.
+    pub fn inverse(&self) -> Self {
+        let mut source = vec![0; self.len()];
+        for (index, &from) in self.source.iter().enumerate() {
+            source[from] = index;
+        }
+        Self { source }
+    }
+
+    /// The cycles of `index -> source_of(index)`, each starting from its lowest index.
+    pub fn cycles(&self) -> Vec<Vec<usize>> {
+        let mut seen = vec![false; self.len()];
+        let mut cycles = Vec::new();
+        for start in 0..self.len() {
+            if seen[start] {
+                continue;
+            }
+            let mut cycle = Vec::new();
+            let mut index = start;
+            while !seen[index] {
+                seen[index] = true;
+                cycle.push(index);
+                index = self.source[index];
+            }
+            cycles.push(cycle);
+        }
+        cycles
+    }
+
+    /// The rearrangement made by applying `self` `k` times, found by walking `k` steps around each cycle at once.
+    pub fn pow(&self, k: u64) -> Self {
+        let mut source = vec![0; self.len()];
+        for cycle in self.cycles() {
+            let shift = (k % cycle.len() as u64) as usize;
+            for (pos, &index) in cycle.iter().enumerate() {
+                source[index] = cycle[(pos + shift) % cycle.len()];
+            }
+        }
+        Self { source }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProgramError {
+    /// A rotation would copy one track over another, because its first or last two indices are the same.
+    RepeatedIndex(Instruction),
+    OutOfRange {
+        index: usize,
+        len: usize,
+    },
+}
+
+impl Display for ProgramError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RepeatedIndex(instruction) => write!(f, "{instruction:?} repeats an index"),
+            Self::OutOfRange { index, len } => write!(f, "index {index} is out of range for {len} tracks"),
+        }
+    }
+}
+
+impl std::error::Error for ProgramError {}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    fn random_program(rng: &mut StdRng, len: usize) -> Vec<Instruction> {
+        (0..rng.gen_range(0..8))
+            .map(|_| {
+                let [x, y, z] = [(); 3].map(|()| rng.gen_range(0..len));
+                match rng.gen_range(0..3) {
+                    0 => Instruction::Swap(x, y),
+                    1 => Instruction::Rotate(x, y, z),
+                    _ => Instruction::BlockSwap(x, y),
+                }
+            })
+            .collect()
+    }
+
+    fn run(program: &[Instruction], items: &mut [usize]) {
+        program.iter().for_each(|instruction| instruction.apply(items));
+    }
+
+    #[test]
+    fn compiled_programs_match_running_them() {
+        let mut rng = StdRng::seed_from_u64(0x7137_4491_b5c0_fbcf);
+        let mut rejected = 0;
+        for _ in 0..5000 {
+            let len = rng.gen_range(1..=8);
+            let program = random_program(&mut rng, len);
+            let mut direct = (0..len).collect::<Vec<_>>();
+            run(&program, &mut direct);
+
+            match Permutation::compile(len, &program) {
+                Ok(permutation) => assert_eq!(permutation.apply(&(0..len).collect::<Vec<_>>()), direct),
+                Err(ProgramError::RepeatedIndex(instruction)) => {
+                    // Only programs that really would lose a track are rejected.
+                    let mut items = (0..len).collect::<Vec<_>>();
+                    instruction.apply(&mut items);
+                    items.sort_unstable();
+                    items.dedup();
+                    assert!(items.len() < len);
+                    rejected += 1;
+                }
+                Err(err) => panic!("{err}"),
+            }
+        }
+        assert!(rejected > 0);
+    }
+
+    #[test]
+    fn rotations_with_repeated_indices() {
+        assert_eq!(
+            Permutation::compile(3, &[Instruction::Rotate(0, 1, 0)]),
+            Permutation::compile(3, &[Instruction::Swap(0, 1)])
+        );
+        assert_eq!(
+            Permutation::compile(3, &[Instruction::Rotate(2, 2, 2)]),
+            Ok(Permutation::identity(3))
+        );
+        for lossy in [Instruction::Rotate(0, 0, 1), Instruction::Rotate(0, 1, 1)] {
+            assert_eq!(
+                Permutation::compile(3, &[lossy]),
+                Err(ProgramError::RepeatedIndex(lossy))
+            );
+        }
+        assert_eq!(
+            Permutation::compile(3, &[Instruction::Swap(0, 3)]),
+            Err(ProgramError::OutOfRange { index: 3, len: 3 })
+        );
+    }
+
+    #[test]
+    fn compose_invert_and_power() {
+        let mut rng = StdRng::seed_from_u64(0xe9b5_dba5_3956_c25b);
+        for _ in 0..1000 {
+            let len = rng.gen_range(1..=10);
+            let (Ok(p), Ok(q)) = (
+                Permutation::compile(len, &random_program(&mut rng, len)),
+                Permutation::compile(len, &random_program(&mut rng, len)),
+            ) else {
+                continue;
+            };
+            let identity = Permutation::identity(len);
+            let items = (0..len).map(|i| i * 10).collect::<Vec<_>>();
+
+            assert_eq!(p.then(&q).apply(&items), q.apply(&p.apply(&items)));
+            assert_eq!(p.then(&p.inverse()), identity);
+            assert_eq!(p.inverse().then(&p), identity);
+            for index in 0..len {
+                assert_eq!(p.inverse().source_of(p.source_of(index)), index);
+            }
+
+            let k = rng.gen_range(0..30);
+            let repeated = (0..k).fold(identity.clone(), |acc, _| acc.then(&p));
+            assert_eq!(p.pow(k), repeated);
+            assert_eq!(p.pow(k).then(&p.pow(k).inverse()), identity);
+        }
+    }
+
+    #[test]
+    fn huge_powers_use_the_cycle_lengths() {
+        // A 5-cycle and a 3-cycle repeat every 15 applications.
+        let program = [
+            Instruction::Swap(0, 1),
+            Instruction::Swap(1, 2),
+            Instruction::Swap(2, 3),
+            Instruction::Swap(3, 4),
+            Instruction::Rotate(5, 6, 7),
+        ];
+        let p = Permutation::compile(8, &program).unwrap();
+        let mut cycle_lengths = p.cycles().iter().map(Vec::len).collect::<Vec<_>>();
+        cycle_lengths.sort_unstable();
+        assert_eq!(cycle_lengths, [3, 5]);
+
+        let k = 1_000_000_000_000;
+        assert_eq!(p.pow(k), p.pow(k % 15));
+        assert_eq!(p.pow(15), Permutation::identity(8));
+    }
+}
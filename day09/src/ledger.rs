@@ -0,0 +1,604 @@
+use std::{
+    cmp::{min, Reverse},
+    collections::{BinaryHeap, HashMap, VecDeque},
+    fmt::Display,
+};
+
+/// Parse a `NAME HAS balance` line.
+pub fn parse_balance_line(line: &str) -> (&str, i64) {
+    let (name, balance) = line.split_once(" HAS ").unwrap();
+    (name, balance.parse().unwrap())
+}
+
+/// Parse a `FROM x TO y AMT n` line.
+pub fn parse_txn_line(line: &str) -> (&str, &str, i64) {
+    let mut iter = line.split_whitespace().skip(1).step_by(2);
+    let from = iter.next().unwrap();
+    let to = iter.next().unwrap();
+    let amt = iter.next().unwrap().parse().unwrap();
+    (from, to, amt)
+}
+
+/// Something that happened to the ledger, with accounts given by index.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    Opened {
+        account: usize,
+        balance: i64,
+    },
+    /// A transaction asked for `requested` and `moved` of it actually changed hands.
+    Transfer {
+        from: usize,
+        to: usize,
+        requested: i64,
+        moved: i64,
+    },
+    DebtRecorded {
+        debtor: usize,
+        creditor: usize,
+        amt: i64,
+    },
+    Repayment {
+        debtor: usize,
+        creditor: usize,
+        amt: i64,
+    },
+}
+
+/// How a transaction moves money when the sender might not have enough of it.
+pub trait SettlementPolicy {
+    /// Move money for one transaction, logging what happened.
+    fn transfer(&mut self, balances: &mut [i64], from: usize, to: usize, amt: i64, log: &mut Vec<Event>);
+
+    /// Called after an account's balance was set directly rather than by a transaction.
+    fn balance_set(&mut self, _balances: &[i64], _account: usize) {}
+}
+
+/// Move the full amount, letting balances go negative.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Unchecked;
+
+impl SettlementPolicy for Unchecked {
+    fn transfer(&mut self, balances: &mut [i64], from: usize, to: usize, amt: i64, log: &mut Vec<Event>) {
+        balances[from] -= amt;
+        balances[to] += amt;
+        log.push(Event::Transfer {
+            from,
+            to,
+            requested: amt,
+            moved: amt,
+        });
+    }
+}
+
+/// Move at most what the sender has, forgetting about the rest.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Capped;
+
+impl SettlementPolicy for Capped {
+    fn transfer(&mut self, balances: &mut [i64], from: usize, to: usize, requested: i64, log: &mut Vec<Event>) {
+        let amt = min(requested, balances[from]);
+        balances[from] -= amt;
+        balances[to] += amt;
+        log.push(Event::Transfer {
+            from,
+            to,
+            requested,
+            moved: amt,
+        });
+    }
+}
+
+/// Move at most what the sender has and record the rest as a debt, which is repaid as soon as the debtor has money,
+/// oldest debts first.
+///
+/// Debts are kept in one FIFO queue per debtor, and the debtors that can repay something right now are kept in a heap
+/// keyed by the age of their oldest debt, so each repayment costs O(log n) instead of a scan over every debt. Only the
+/// receiving side of a transfer or an account whose balance was set directly can start repaying, so those are the only
+/// accounts that need to be put back on the worklist.
+#[derive(Clone, Debug, Default)]
+pub struct RepayDebts {
+    /// Each debtor's unpaid debts as `(sequence number, creditor, amount)`, oldest first.
+    queues: Vec<VecDeque<(u64, usize, i64)>>,
+    /// Debtors that might be able to repay, keyed by the sequence number of their oldest debt. Entries can go stale,
+    /// so they're checked again when popped.
+    ready: BinaryHeap<Reverse<(u64, usize)>>,
+    next_seq: u64,
+}
+
+impl RepayDebts {
+    fn queue(&mut self, debtor: usize) -> &mut VecDeque<(u64, usize, i64)> {
+        if self.queues.len() <= debtor {
+            self.queues.resize_with(debtor + 1, VecDeque::new);
+        }
+        &mut self.queues[debtor]
+    }
+
+    /// Put `account` on the worklist if it has both money and debts.
+    fn mark_ready(&mut self, balances: &[i64], account: usize) {
+        if balances[account] > 0 {
+            if let Some(&(seq, _, _)) = self.queues.get(account).and_then(|queue| queue.front()) {
+                self.ready.push(Reverse((seq, account)));
+            }
+        }
+    }
+
+    /// Debts that haven't been fully repaid yet, as `(debtor, creditor, amount)`, oldest first.
+    pub fn outstanding(&self) -> impl Iterator<Item = (usize, usize, i64)> + '_ {
+        let mut debts = self
+            .queues
+            .iter()
+            .enumerate()
+            .flat_map(|(debtor, queue)| {
+                queue
+                    .iter()
+                    .map(move |&(seq, creditor, amt)| (seq, debtor, creditor, amt))
+            })
+            .collect::<Vec<_>>();
+        debts.sort_unstable_by_key(|&(seq, ..)| seq);
+        debts
+            .into_iter()
+            .map(|(_, debtor, creditor, amt)| (debtor, creditor, amt))
+    }
+}
+
+impl SettlementPolicy for RepayDebts {
+    fn transfer(&mut self, balances: &mut [i64], from: usize, to: usize, requested: i64, log: &mut Vec<Event>) {
+        let mut amt = requested;
+        if balances[from] < amt {
+            let seq = self.next_seq;
+            self.next_seq += 1;
+            self.queue(from).push_back((seq, to, amt - balances[from]));
+            amt = balances[from];
+        }
+        balances[from] -= amt;
+        balances[to] += amt;
+        log.push(Event::Transfer {
+            from,
+            to,
+            requested,
+            moved: amt,
+        });
+        if amt < requested {
+            log.push(Event::DebtRecorded {
+                debtor: from,
+                creditor: to,
+                amt: requested - amt,
+            });
+        }
+
+        self.mark_ready(balances, to);
+        while let Some(Reverse((seq, debtor))) = self.ready.pop() {
+            let queue = &mut self.queues[debtor];
+            let Some((front_seq, creditor, debt_amt)) = queue.front_mut() else {
+                continue;
+            };
+            if *front_seq != seq || balances[debtor] <= 0 {
+                continue;
+            }
+
+            let creditor = *creditor;
+            let can_return = min(*debt_amt, balances[debtor]);
+            balances[debtor] -= can_return;
+            balances[creditor] += can_return;
+            *debt_amt -= can_return;
+            if *debt_amt == 0 {
+                queue.pop_front();
+            }
+            log.push(Event::Repayment {
+                debtor,
+                creditor,
+                amt: can_return,
+            });
+
+            self.mark_ready(balances, debtor);
+            self.mark_ready(balances, creditor);
+        }
+    }
+
+    /// The account can repay from the next transaction on, like any other debtor with money.
+    fn balance_set(&mut self, balances: &[i64], account: usize) {
+        self.mark_ready(balances, account);
+    }
+}
+
+/// Account balances keyed by name, moved around according to a settlement policy.
+#[derive(Clone, Debug, Default)]
+pub struct Ledger<'a, P> {
+    names: Vec<&'a str>,
+    indices: HashMap<&'a str, usize>,
+    balances: Vec<i64>,
+    policy: P,
+    /// Only kept when `audit` is set; otherwise whatever the policy logs is dropped straight after each transfer.
+    log: Vec<Event>,
+    audit: bool,
+    /// The sum of every opening balance, which no transaction should change.
+    opening_total: i64,
+}
+
+impl<'a, P: SettlementPolicy> Ledger<'a, P> {
+    /// An empty ledger that doesn't keep an event log.
+    pub fn new(policy: P) -> Self {
+        Self {
+            names: Vec::new(),
+            indices: HashMap::new(),
+            balances: Vec::new(),
+            policy,
+            log: Vec::new(),
+            audit: false,
+            opening_total: 0,
+        }
+    }
+
+    /// An empty ledger that logs every event, for [`Ledger::events`] and [`Ledger::audit_trail`].
+    pub fn with_audit(policy: P) -> Self {
+        Self {
+            audit: true,
+            ..Self::new(policy)
+        }
+    }
+
+    /// Build a ledger without an event log from the opening balances, a blank line, and then the transactions.
+    pub fn from_input(input: &'a str, policy: P) -> Self {
+        let mut ledger = Self::new(policy);
+        ledger.apply_input(input);
+        ledger
+    }
+
+    /// Open the accounts and run the transactions from input formatted as for [`Ledger::from_input`].
+    pub fn apply_input(&mut self, input: &'a str) {
+        let (balances, transactions) = input.split_once("\n\n").unwrap_or((input, ""));
+        for (name, balance) in balances.lines().map(parse_balance_line) {
+            self.open(name, balance);
+        }
+        for (from, to, amt) in transactions.lines().map(parse_txn_line) {
+            self.transfer(from, to, amt);
+        }
+    }
+
+    /// The index of the account with the given name, opening it with a zero balance if it doesn't exist yet.
+    fn account(&mut self, name: &'a str) -> usize {
+        *self.indices.entry(name).or_insert_with(|| {
+            self.names.push(name);
+            self.balances.push(0);
+            self.names.len() - 1
+        })
+    }
+
+    /// Set the balance of an account, opening it if needed.
+    pub fn open(&mut self, name: &'a str, balance: i64) {
+        let idx = self.account(name);
+        self.opening_total += balance - self.balances[idx];
+        self.balances[idx] = balance;
+        if self.audit {
+            self.log.push(Event::Opened { account: idx, balance });
+        }
+        self.policy.balance_set(&self.balances, idx);
+    }
+
+    pub fn transfer(&mut self, from: &'a str, to: &'a str, amt: i64) {
+        let from = self.account(from);
+        let to = self.account(to);
+        self.policy.transfer(&mut self.balances, from, to, amt, &mut self.log);
+        if !self.audit {
+            self.log.clear();
+        }
+    }
+
+    pub fn name(&self, account: usize) -> &'a str {
+        self.names[account]
+    }
+
+    /// Everything that has happened to the ledger, in order, or nothing if it wasn't made [`Ledger::with_audit`].
+    pub fn events(&self) -> &[Event] {
+        &self.log
+    }
+
+    /// The event log, one human-readable line per event, each with the balances it left behind.
+    pub fn audit_trail(&self) -> String {
+        let mut balances = vec![0; self.names.len()];
+        let mut trail = String::new();
+        for event in &self.log {
+            let line = match *event {
+                Event::Opened { account, balance } => {
+                    balances[account] = balance;
+                    format!("open {} with {balance}", self.names[account])
+                }
+                Event::Transfer {
+                    from,
+                    to,
+                    requested,
+                    moved,
+                } => {
+                    balances[from] -= moved;
+                    balances[to] += moved;
+                    format!("{} pays {} {moved} of {requested}", self.names[from], self.names[to])
+                }
+                Event::DebtRecorded { debtor, creditor, amt } => {
+                    format!("{} owes {} {amt}", self.names[debtor], self.names[creditor])
+                }
+                Event::Repayment { debtor, creditor, amt } => {
+                    balances[debtor] -= amt;
+                    balances[creditor] += amt;
+                    format!("{} repays {} {amt}", self.names[debtor], self.names[creditor])
+                }
+            };
+
+            let touched = match *event {
+                Event::Opened { account, .. } => vec![account],
+                Event::Transfer { from, to, .. } => vec![from, to],
+                Event::DebtRecorded { debtor, creditor, .. } | Event::Repayment { debtor, creditor, .. } => {
+                    vec![debtor, creditor]
+                }
+            };
+            let after = touched
+                .into_iter()
+                .map(|idx| format!("{}={}", self.names[idx], balances[idx]))
+                .collect::<Vec<_>>()
+                .join(" ");
+            trail.push_str(&format!("{line:<40} [{after}]\n"));
+        }
+        trail
+    }
+
+    /// Check that transactions have only moved money around, never creating or destroying any.
+    pub fn check_conservation(&self) -> Result<(), ConservationError> {
+        let total = self.balances.iter().sum();
+        if total == self.opening_total {
+            Ok(())
+        } else {
+            Err(ConservationError {
+                expected: self.opening_total,
+                found: total,
+            })
+        }
+    }
+
+    pub fn balance(&self, name: &str) -> Option<i64> {
+        self.indices.get(name).map(|&idx| self.balances[idx])
+    }
+
+    /// Every account and its balance, in the order they were opened.
+    pub fn accounts(&self) -> impl Iterator<Item = (&'a str, i64)> + '_ {
+        self.names.iter().copied().zip(self.balances.iter().copied())
+    }
+
+    pub fn policy(&self) -> &P {
+        &self.policy
+    }
+
+    pub fn sum_top_three(&self) -> i64 {
+        let mut balances = self.balances.clone();
+        balances.sort_unstable_by(|a, b| b.cmp(a));
+        balances.into_iter().take(3).sum()
+    }
+}
+
+impl<'a> Ledger<'a, RepayDebts> {
+    /// What is still owed, per debt and totalled per debtor and per creditor.
+    pub fn debt_report(&self) -> DebtReport<'a> {
+        let debts = self
+            .policy
+            .outstanding()
+            .map(|(debtor, creditor, amt)| (self.names[debtor], self.names[creditor], amt))
+            .collect::<Vec<_>>();
+
+        let totals = |key: fn(&(&'a str, &'a str, i64)) -> &'a str| {
+            let mut totals = HashMap::<&str, i64>::new();
+            for debt in &debts {
+                *totals.entry(key(debt)).or_default() += debt.2;
+            }
+            let mut totals = totals.into_iter().collect::<Vec<_>>();
+            totals.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+            totals
+        };
+
+        DebtReport {
+            by_debtor: totals(|debt| debt.0),
+            by_creditor: totals(|debt| debt.1),
+            debts,
+        }
+    }
+}
+
+/// Outstanding debts at the end of the ledger.
+#[derive(Clone, Debug, Default)]
+pub struct DebtReport<'a> {
+    /// Every unpaid debt as `(debtor, creditor, amount)`, oldest first.
+    pub debts: Vec<(&'a str, &'a str, i64)>,
+    /// Total owed by each debtor, largest first.
+    pub by_debtor: Vec<(&'a str, i64)>,
+    /// Total owed to each creditor, largest first.
+    pub by_creditor: Vec<(&'a str, i64)>,
+}
+
+impl Display for DebtReport<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "outstanding debts:")?;
+        for (debtor, creditor, amt) in &self.debts {
+            writeln!(f, "  {debtor} owes {creditor} {amt}")?;
+        }
+        writeln!(f, "owed by:")?;
+        for (debtor, amt) in &self.by_debtor {
+            writeln!(f, "  {debtor:<10} {amt:>8}")?;
+        }
+        writeln!(f, "owed to:")?;
+        for (creditor, amt) in &self.by_creditor {
+            writeln!(f, "  {creditor:<10} {amt:>8}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Transactions changed the total amount of money in the ledger.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConservationError {
+    pub expected: i64,
+    pub found: i64,
+}
+
+impl Display for ConservationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ledger should hold {} in total, but holds {}",
+            self.expected, self.found
+        )
+    }
+}
+
+impl std::error::Error for ConservationError {}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    /// The original `RepayDebts`, which rescans every debt after each transaction, kept as a reference.
+    #[derive(Default)]
+    struct ScanRepayDebts {
+        debts: Vec<(usize, usize, i64)>,
+    }
+
+    impl SettlementPolicy for ScanRepayDebts {
+        fn transfer(&mut self, balances: &mut [i64], from: usize, to: usize, requested: i64, log: &mut Vec<Event>) {
+            let mut amt = requested;
+            if balances[from] < amt {
+                self.debts.push((from, to, amt - balances[from]));
+                amt = balances[from];
+            }
+            balances[from] -= amt;
+            balances[to] += amt;
+            log.push(Event::Transfer {
+                from,
+                to,
+                requested,
+                moved: amt,
+            });
+            if amt < requested {
+                log.push(Event::DebtRecorded {
+                    debtor: from,
+                    creditor: to,
+                    amt: requested - amt,
+                });
+            }
+
+            while let Some((debt_from, debt_to, debt_amt)) =
+                self.debts.iter_mut().find(|(from, _, _)| balances[*from] > 0)
+            {
+                let can_return = min(*debt_amt, balances[*debt_from]);
+                balances[*debt_from] -= can_return;
+                balances[*debt_to] += can_return;
+                *debt_amt -= can_return;
+                log.push(Event::Repayment {
+                    debtor: *debt_from,
+                    creditor: *debt_to,
+                    amt: can_return,
+                });
+                self.debts.retain(|&(_, _, amt)| amt > 0);
+            }
+        }
+    }
+
+    /// Run the same random opens and transfers through both policies.
+    fn random_ledgers<'a>(
+        rng: &mut StdRng,
+        names: &'a [String],
+        steps: usize,
+    ) -> (Ledger<'a, RepayDebts>, Ledger<'a, ScanRepayDebts>) {
+        let mut queued = Ledger::with_audit(RepayDebts::default());
+        let mut scanned = Ledger::with_audit(ScanRepayDebts::default());
+        let name = |rng: &mut StdRng| names[rng.gen_range(0..names.len())].as_str();
+
+        for _ in 0..steps {
+            if rng.gen_range(0..10) == 0 {
+                let (account, balance) = (name(rng), rng.gen_range(0..100));
+                queued.open(account, balance);
+                scanned.open(account, balance);
+            } else {
+                let (from, to, amt) = (name(rng), name(rng), rng.gen_range(1..=100));
+                queued.transfer(from, to, amt);
+                scanned.transfer(from, to, amt);
+            }
+        }
+        (queued, scanned)
+    }
+
+    #[test]
+    fn queues_match_rescanning() {
+        let mut rng = StdRng::seed_from_u64(0x243f_6a88_85a3_08d3);
+        for _ in 0..500 {
+            let names = (0..rng.gen_range(1..=8)).map(|i| format!("A{i}")).collect::<Vec<_>>();
+            let steps = rng.gen_range(0..60);
+            let (queued, scanned) = random_ledgers(&mut rng, &names, steps);
+
+            assert_eq!(
+                queued.accounts().collect::<Vec<_>>(),
+                scanned.accounts().collect::<Vec<_>>()
+            );
+            assert_eq!(queued.events(), scanned.events());
+            assert_eq!(
+                queued.policy().outstanding().collect::<Vec<_>>(),
+                scanned.policy().debts
+            );
+            queued.check_conservation().unwrap();
+        }
+    }
+
+    #[test]
+    fn only_audited_ledgers_keep_events() {
+        let input = "A HAS 10\nB HAS 0\n\nFROM A TO B AMT 15\nFROM B TO A AMT 5\n";
+        let plain = Ledger::from_input(input, RepayDebts::default());
+        assert!(plain.events().is_empty());
+        assert_eq!(plain.audit_trail(), "");
+
+        let mut audited = Ledger::with_audit(RepayDebts::default());
+        audited.apply_input(input);
+        assert_eq!(audited.events().len(), 6);
+        assert_eq!(
+            plain.accounts().collect::<Vec<_>>(),
+            audited.accounts().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn opening_a_debtor_lets_it_repay() {
+        let mut ledger = Ledger::new(RepayDebts::default());
+        for name in ["A", "B", "C"] {
+            ledger.open(name, 0);
+        }
+        ledger.transfer("A", "B", 10);
+        ledger.open("A", 50);
+        ledger.transfer("C", "C", 0);
+
+        assert_eq!(ledger.balance("A"), Some(40));
+        assert_eq!(ledger.balance("B"), Some(10));
+        assert_eq!(ledger.policy().outstanding().count(), 0);
+    }
+
+    #[test]
+    fn million_transactions() {
+        let mut rng = StdRng::seed_from_u64(0x1319_8a2e_0370_7344);
+        let names = (0..1000).map(|i| format!("A{i}")).collect::<Vec<_>>();
+        let mut ledger = Ledger::new(RepayDebts::default());
+        for name in &names {
+            ledger.open(name, rng.gen_range(0..1000));
+        }
+        for _ in 0..1_000_000 {
+            let from = &names[rng.gen_range(0..1000)];
+            let to = &names[rng.gen_range(0..1000)];
+            ledger.transfer(from, to, rng.gen_range(1..=500));
+        }
+
+        ledger.check_conservation().unwrap();
+        assert!(ledger.accounts().all(|(_, balance)| balance >= 0));
+        // Nobody with money can still be in debt, or they would have repaid.
+        let debtors = ledger
+            .policy()
+            .outstanding()
+            .map(|(debtor, ..)| debtor)
+            .collect::<Vec<_>>();
+        assert!(debtors.iter().all(|&debtor| ledger.balances[debtor] == 0));
+    }
+}
@@ -0,0 +1,445 @@
+use std::{fmt::Display, ops::Index, str::FromStr};
+
+use pathfinding::prelude::{bfs, dijkstra};
+
+/// A cell position as `(row, column)`.
+pub type Cell = (usize, usize);
+
+/// A rectangular grid of danger levels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid {
+    width: usize,
+    height: usize,
+    cells: Vec<u64>,
+}
+
+impl Grid {
+    /// Build a grid from its cells in row-major order.
+    pub fn new(width: usize, height: usize, cells: Vec<u64>) -> Self {
+        assert!(width > 0, "grid must be at least one cell wide");
+        assert_eq!(cells.len(), width * height, "grid has the wrong number of cells");
+        Self { width, height, cells }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (row, col): Cell) -> bool {
+        row < self.height && col < self.width
+    }
+
+    pub fn row(&self, row: usize) -> &[u64] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = u64> + '_ {
+        self.cells.iter().skip(col).step_by(self.width).copied()
+    }
+
+    /// The cells reachable from `cell` in a single move.
+    pub fn neighbours(&self, (row, col): Cell, moves: MoveSet) -> impl Iterator<Item = Cell> + '_ {
+        moves.offsets().iter().filter_map(move |&(dr, dc)| {
+            let next = (row.checked_add_signed(dr)?, col.checked_add_signed(dc)?);
+            self.contains(next).then_some(next)
+        })
+    }
+}
+
+impl Index<Cell> for Grid {
+    type Output = u64;
+
+    fn index(&self, (row, col): Cell) -> &Self::Output {
+        assert!(self.contains((row, col)), "cell ({row}, {col}) is outside the grid");
+        &self.cells[row * self.width + col]
+    }
+}
+
+#[derive(Debug)]
+pub enum ParseGridError {
+    Empty,
+    Ragged { row: usize },
+    Cell(std::num::ParseIntError),
+}
+
+impl Display for ParseGridError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "grid is empty"),
+            Self::Ragged { row } => write!(f, "row {row} has a different width from the first row"),
+            Self::Cell(err) => write!(f, "invalid cell: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseGridError {}
+
+impl FromStr for Grid {
+    type Err = ParseGridError;
+
+    /// Parse whitespace-separated danger levels, one grid row per line.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut width = None;
+        let mut cells = Vec::new();
+        let mut height = 0;
+
+        for (row, line) in s.lines().filter(|line| !line.trim().is_empty()).enumerate() {
+            let before = cells.len();
+            for value in line.split_ascii_whitespace() {
+                cells.push(value.parse().map_err(ParseGridError::Cell)?);
+            }
+
+            let row_width = cells.len() - before;
+            if *width.get_or_insert(row_width) != row_width {
+                return Err(ParseGridError::Ragged { row });
+            }
+            height += 1;
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok(Self::new(width, height, cells)),
+            _ => Err(ParseGridError::Empty),
+        }
+    }
+}
+
+/// Which moves are allowed from one cell to the next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveSet {
+    /// Only one step right or one step down.
+    RightDown,
+    /// Horizontal and vertical steps in both directions.
+    FourWay,
+    /// Horizontal, vertical and diagonal steps in both directions.
+    EightWay,
+}
+
+impl MoveSet {
+    /// Whether every move goes strictly further from the top-left corner.
+    pub fn is_monotone(self) -> bool {
+        self == Self::RightDown
+    }
+
+    fn offsets(self) -> &'static [(isize, isize)] {
+        match self {
+            Self::RightDown => &[(0, 1), (1, 0)],
+            Self::FourWay => &[(0, 1), (1, 0), (0, -1), (-1, 0)],
+            Self::EightWay => &[(0, 1), (1, 0), (0, -1), (-1, 0), (1, 1), (1, -1), (-1, 1), (-1, -1)],
+        }
+    }
+}
+
+/// How the danger of a path is computed from the danger of its cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CostModel {
+    /// Total danger of every cell on the path.
+    Sum,
+    /// Danger of the most dangerous cell on the path.
+    Max,
+}
+
+/// A safest path, including both of its endpoints.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DangerPath {
+    pub cost: u64,
+    pub path: Vec<Cell>,
+}
+
+/// Which algorithm computes the safest path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// Use the DP backend whenever the move set allows it, and graph search otherwise.
+    Auto,
+    /// Graph search, which works with any move set.
+    Search,
+    /// A single dynamic programming pass, which only works with monotone move sets.
+    Dp,
+}
+
+/// Find the safest path from `start` to `target`, or `None` if `target` can't be reached.
+///
+/// The danger of both `start` and `target` counts towards the cost.
+pub fn safest_path(grid: &Grid, moves: MoveSet, start: Cell, target: Cell, cost: CostModel) -> Option<DangerPath> {
+    safest_path_with(grid, moves, start, target, cost, Backend::Auto)
+}
+
+/// Like [`safest_path`], but with an explicit choice of backend.
+///
+/// Both backends always agree on the cost, but may pick different paths when there's a tie.
+///
+/// # Panics
+///
+/// Panics if [`Backend::Dp`] is requested for a move set that isn't monotone.
+pub fn safest_path_with(
+    grid: &Grid,
+    moves: MoveSet,
+    start: Cell,
+    target: Cell,
+    cost: CostModel,
+    backend: Backend,
+) -> Option<DangerPath> {
+    if !grid.contains(start) || !grid.contains(target) {
+        return None;
+    }
+
+    match backend {
+        Backend::Auto if moves.is_monotone() => monotone_dp(grid, start, target, cost),
+        Backend::Auto | Backend::Search => search(grid, moves, start, target, cost),
+        Backend::Dp => {
+            assert!(
+                moves.is_monotone(),
+                "the DP backend needs a monotone move set, not {moves:?}"
+            );
+            monotone_dp(grid, start, target, cost)
+        }
+    }
+}
+
+fn search(grid: &Grid, moves: MoveSet, start: Cell, target: Cell, cost: CostModel) -> Option<DangerPath> {
+    match cost {
+        CostModel::Sum => {
+            let (path, cost) = dijkstra(
+                &start,
+                |&cell| grid.neighbours(cell, moves).map(|next| (next, grid[next])),
+                |&cell| cell == target,
+            )?;
+            Some(DangerPath {
+                cost: grid[start] + cost,
+                path,
+            })
+        }
+
+        CostModel::Max => {
+            // The bottleneck of the best path is one of the cell values, so binary search for the smallest one that
+            // still lets us through when every more dangerous cell is treated as a wall.
+            let floor = grid[start].max(grid[target]);
+            let mut thresholds = grid.cells.iter().copied().filter(|&v| v >= floor).collect::<Vec<_>>();
+            thresholds.sort_unstable();
+            thresholds.dedup();
+
+            let under = |threshold: u64| {
+                bfs(
+                    &start,
+                    |&cell| {
+                        grid.neighbours(cell, moves)
+                            .filter(move |&next| grid[next] <= threshold)
+                    },
+                    |&cell| cell == target,
+                )
+            };
+
+            let idx = thresholds.partition_point(|&threshold| under(threshold).is_none());
+            let cost = *thresholds.get(idx)?;
+            Some(DangerPath {
+                cost,
+                path: under(cost)?,
+            })
+        }
+    }
+}
+
+/// With only right/down moves every cell's best path comes from the cell above or the one to its left, so a single
+/// row-major pass over the rectangle between `start` and `target` is enough.
+fn monotone_dp(grid: &Grid, start: Cell, target: Cell, cost: CostModel) -> Option<DangerPath> {
+    if target.0 < start.0 || target.1 < start.1 {
+        return None;
+    }
+
+    let height = target.0 - start.0 + 1;
+    let width = target.1 - start.1 + 1;
+    let combine = |acc: u64, here: u64| match cost {
+        CostModel::Sum => acc + here,
+        CostModel::Max => acc.max(here),
+    };
+
+    let mut best = vec![0; width * height];
+    for row in 0..height {
+        for col in 0..width {
+            let here = grid[(start.0 + row, start.1 + col)];
+            let before = match (row, col) {
+                (0, 0) => None,
+                (0, _) => Some(best[col - 1]),
+                (_, 0) => Some(best[(row - 1) * width]),
+                _ => Some(best[(row - 1) * width + col].min(best[row * width + col - 1])),
+            };
+            best[row * width + col] = before.map_or(here, |acc| combine(acc, here));
+        }
+    }
+
+    let mut path = Vec::with_capacity(width + height - 1);
+    let (mut row, mut col) = (height - 1, width - 1);
+    path.push(target);
+    while (row, col) != (0, 0) {
+        let from_above = col == 0 || (row > 0 && best[(row - 1) * width + col] <= best[row * width + col - 1]);
+        if from_above {
+            row -= 1;
+        } else {
+            col -= 1;
+        }
+        path.push((start.0 + row, start.1 + col));
+    }
+    path.reverse();
+
+    Some(DangerPath {
+        cost: best[width * height - 1],
+        path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    fn random_grid(rng: &mut StdRng, width: usize, height: usize) -> Grid {
+        let cells = (0..width * height).map(|_| rng.gen_range(0..10)).collect();
+        Grid::new(width, height, cells)
+    }
+
+    fn random_cell(rng: &mut StdRng, grid: &Grid) -> Cell {
+        (rng.gen_range(0..grid.height()), rng.gen_range(0..grid.width()))
+    }
+
+    fn path_cost(grid: &Grid, path: &[Cell], cost: CostModel) -> u64 {
+        let dangers = path.iter().map(|&cell| grid[cell]);
+        match cost {
+            CostModel::Sum => dangers.sum(),
+            CostModel::Max => dangers.max().unwrap(),
+        }
+    }
+
+    fn assert_valid(grid: &Grid, moves: MoveSet, path: &DangerPath, start: Cell, target: Cell, cost: CostModel) {
+        assert_eq!(path.path.first(), Some(&start));
+        assert_eq!(path.path.last(), Some(&target));
+        for step in path.path.windows(2) {
+            assert!(grid.neighbours(step[0], moves).any(|cell| cell == step[1]));
+        }
+        assert_eq!(path_cost(grid, &path.path, cost), path.cost);
+    }
+
+    fn cells(grid: &Grid) -> impl Iterator<Item = Cell> + '_ {
+        (0..grid.height()).flat_map(|row| (0..grid.width()).map(move |col| (row, col)))
+    }
+
+    /// Bellman-Ford: relax every move until nothing changes.
+    fn bellman_ford(grid: &Grid, moves: MoveSet, start: Cell, target: Cell) -> Option<u64> {
+        let mut best = vec![None; grid.width() * grid.height()];
+        let index = |(row, col): Cell| row * grid.width() + col;
+        best[index(start)] = Some(grid[start]);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for cell in cells(grid) {
+                let Some(cost) = best[index(cell)] else { continue };
+                for next in grid.neighbours(cell, moves) {
+                    let candidate = cost + grid[next];
+                    if best[index(next)].is_none_or(|old| candidate < old) {
+                        best[index(next)] = Some(candidate);
+                        changed = true;
+                    }
+                }
+            }
+        }
+        best[index(target)]
+    }
+
+    /// Try every danger level in the grid as a threshold, lowest first, and flood fill the cells at or below it.
+    fn bottleneck(grid: &Grid, moves: MoveSet, start: Cell, target: Cell) -> Option<u64> {
+        let mut levels = cells(grid).map(|cell| grid[cell]).collect::<Vec<_>>();
+        levels.sort_unstable();
+        levels.into_iter().find(|&level| {
+            let mut seen = vec![start];
+            let mut stack = vec![start];
+            while let Some(cell) = stack.pop() {
+                for next in grid.neighbours(cell, moves) {
+                    if grid[next] <= level && !seen.contains(&next) {
+                        seen.push(next);
+                        stack.push(next);
+                    }
+                }
+            }
+            grid[start] <= level && seen.contains(&target)
+        })
+    }
+
+    #[test]
+    fn dp_agrees_with_search() {
+        let mut rng = StdRng::seed_from_u64(0x2545_f491_4f6c_dd1d);
+        for _ in 0..500 {
+            let (width, height) = (rng.gen_range(1..=12), rng.gen_range(1..=12));
+            let grid = random_grid(&mut rng, width, height);
+            let (start, target) = (random_cell(&mut rng, &grid), random_cell(&mut rng, &grid));
+
+            for cost in [CostModel::Sum, CostModel::Max] {
+                let dp = safest_path_with(&grid, MoveSet::RightDown, start, target, cost, Backend::Dp);
+                let search = safest_path_with(&grid, MoveSet::RightDown, start, target, cost, Backend::Search);
+                assert_eq!(dp.as_ref().map(|p| p.cost), search.as_ref().map(|p| p.cost));
+
+                let reachable = start.0 <= target.0 && start.1 <= target.1;
+                assert_eq!(dp.is_some(), reachable);
+                for path in dp.iter().chain(&search) {
+                    assert_valid(&grid, MoveSet::RightDown, path, start, target, cost);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn matches_reference_for_every_move_set() {
+        let mut rng = StdRng::seed_from_u64(0x6c62_272e_07bb_0142);
+        for _ in 0..300 {
+            let (width, height) = (rng.gen_range(1..=7), rng.gen_range(1..=7));
+            let grid = random_grid(&mut rng, width, height);
+            let (start, target) = (random_cell(&mut rng, &grid), random_cell(&mut rng, &grid));
+
+            for moves in [MoveSet::RightDown, MoveSet::FourWay, MoveSet::EightWay] {
+                for (cost, reference) in [
+                    (CostModel::Sum, bellman_ford(&grid, moves, start, target)),
+                    (CostModel::Max, bottleneck(&grid, moves, start, target)),
+                ] {
+                    let path = safest_path(&grid, moves, start, target, cost);
+                    assert_eq!(path.as_ref().map(|p| p.cost), reference, "{moves:?} {cost:?}");
+                    if let Some(path) = &path {
+                        assert_valid(&grid, moves, path, start, target, cost);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one cell wide")]
+    fn zero_width_grid_is_rejected() {
+        Grid::new(0, 3, Vec::new());
+    }
+
+    /// `cargo test --release -p day10 -- --ignored --nocapture` to compare the backends on a big grid.
+    #[test]
+    #[ignore]
+    fn dp_is_faster_on_large_grids() {
+        let mut rng = StdRng::seed_from_u64(0x9e37_79b9_7f4a_7c15);
+        let grid = random_grid(&mut rng, 1000, 1000);
+        let target = (999, 999);
+
+        for cost in [CostModel::Sum, CostModel::Max] {
+            let time = |backend| {
+                let start = Instant::now();
+                let path = safest_path_with(&grid, MoveSet::RightDown, (0, 0), target, cost, backend).unwrap();
+                (path.cost, start.elapsed())
+            };
+            let (dp_cost, dp_time) = time(Backend::Dp);
+            let (search_cost, search_time) = time(Backend::Search);
+
+            println!("{cost:?}: dp {dp_time:?}, search {search_time:?}");
+            assert_eq!(dp_cost, search_cost);
+            assert!(dp_time < search_time);
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod danger_path;
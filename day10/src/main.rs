@@ -1,57 +1,21 @@
-const SIDE: usize = 50;
+use day10::danger_path::{safest_path, CostModel, Grid, MoveSet};
 
-fn parse(input: &str) -> [u8; SIDE * SIDE] {
-    let mut grid = [0; SIDE * SIDE];
-    input
-        .split_ascii_whitespace()
-        .zip(grid.iter_mut())
-        .for_each(|(value, cell)| *cell = value.parse().unwrap());
-
-    grid
-}
-
-fn solve_part1(input: &str) -> impl std::fmt::Display {
-    let grid = parse(input);
-    let mut result = u64::MAX;
-
-    for i in 0..SIDE {
-        let mut row_danger = 0;
-        let mut col_danger = 0;
-
-        for j in 0..SIDE {
-            row_danger += u64::from(grid[i * SIDE + j]);
-            col_danger += u64::from(grid[j * SIDE + i]);
-        }
-
-        result = result.min(row_danger).min(col_danger);
-    }
-
-    result
+fn solve_part1(grid: &Grid) -> impl std::fmt::Display {
+    let rows = (0..grid.height()).map(|row| grid.row(row).iter().sum::<u64>());
+    let cols = (0..grid.width()).map(|col| grid.column(col).sum::<u64>());
+    rows.chain(cols).min().unwrap()
 }
 
-fn solve_part23<const TARGET_SIDE: usize>(input: &str) -> impl std::fmt::Display {
-    let grid = parse(input);
-
-    u64::from(grid[0])
-        + pathfinding::prelude::dijkstra(
-            &(0, 0),
-            |&(x, y)| {
-                [
-                    ((x + 1) < TARGET_SIDE).then(|| ((x + 1, y), u64::from(grid[(x + 1) * SIDE + y]))),
-                    ((y + 1) < TARGET_SIDE).then(|| ((x, y + 1), u64::from(grid[x * SIDE + y + 1]))),
-                ]
-                .into_iter()
-                .flatten()
-            },
-            |&(x, y)| x == (TARGET_SIDE - 1) && y == (TARGET_SIDE - 1),
-        )
+fn solve_part23(grid: &Grid, target_side: usize) -> impl std::fmt::Display {
+    let target = (target_side - 1, target_side - 1);
+    safest_path(grid, MoveSet::RightDown, (0, 0), target, CostModel::Sum)
         .unwrap()
-        .1
+        .cost
 }
 
 fn main() {
-    let input = include_str!("input.txt");
-    println!("{}", solve_part1(input));
-    println!("{}", solve_part23::<15>(input));
-    println!("{}", solve_part23::<SIDE>(input));
+    let grid = include_str!("input.txt").parse::<Grid>().unwrap();
+    println!("{}", solve_part1(&grid));
+    println!("{}", solve_part23(&grid, 15));
+    println!("{}", solve_part23(&grid, grid.width()));
 }
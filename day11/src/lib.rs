@@ -0,0 +1,2 @@
+pub mod numeral;
+pub mod root;
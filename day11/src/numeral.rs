@@ -0,0 +1,285 @@
+use std::fmt::Display;
+
+use num_bigint::BigUint;
+
+/// The puzzle's digits: `0-9`, `A-Z`, `a-z` and then `!@#$%^`, for bases up to 68.
+pub const PUZZLE_DIGITS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!@#$%^";
+
+/// The digits used to write numbers, in order of value.
+#[derive(Clone, Debug)]
+pub struct Alphabet {
+    digits: Vec<u8>,
+    values: [Option<u8>; 128],
+}
+
+impl Alphabet {
+    /// Build an alphabet from its digits, which must all be distinct ASCII characters.
+    pub fn new(digits: &[u8]) -> Result<Self, AlphabetError> {
+        if digits.len() < 2 || digits.len() > 128 {
+            return Err(AlphabetError::Size(digits.len()));
+        }
+
+        let mut values = [None; 128];
+        for (value, &digit) in digits.iter().enumerate() {
+            if !digit.is_ascii() {
+                return Err(AlphabetError::NotAscii(digit));
+            }
+            if values[usize::from(digit)].replace(value as u8).is_some() {
+                return Err(AlphabetError::Duplicate(char::from(digit)));
+            }
+        }
+
+        Ok(Self {
+            digits: digits.to_vec(),
+            values,
+        })
+    }
+
+    pub fn puzzle() -> Self {
+        Self::new(PUZZLE_DIGITS).unwrap()
+    }
+
+    /// The largest base this alphabet can write numbers in.
+    pub fn max_base(&self) -> u32 {
+        self.digits.len() as u32
+    }
+
+    fn check_base(&self, base: u32) -> Result<(), ParseNumeralError> {
+        if (2..=self.max_base()).contains(&base) {
+            Ok(())
+        } else {
+            Err(ParseNumeralError::Base(base))
+        }
+    }
+
+    /// Parse `numeral` in the given base, failing rather than wrapping if it doesn't fit in `N`.
+    pub fn parse<N: Numeral>(&self, numeral: &str, base: u32) -> Result<N, ParseNumeralError> {
+        self.check_base(base)?;
+        if numeral.is_empty() {
+            return Err(ParseNumeralError::Empty);
+        }
+
+        numeral.chars().try_fold(N::zero(), |acc, c| {
+            let digit = self
+                .values
+                .get(c as usize)
+                .copied()
+                .flatten()
+                .map(u32::from)
+                .filter(|&digit| digit < base)
+                .ok_or(ParseNumeralError::Digit { digit: c, base })?;
+            acc.checked_mul_add(base, digit).ok_or(ParseNumeralError::Overflow)
+        })
+    }
+
+    /// Write `n` in the given base.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the alphabet doesn't have enough digits for `base`, or if `base` is less than 2.
+    pub fn format<N: Numeral>(&self, mut n: N, base: u32) -> String {
+        assert!(
+            (2..=self.max_base()).contains(&base),
+            "base {base} isn't supported by this alphabet"
+        );
+        if n.is_zero() {
+            return char::from(self.digits[0]).to_string();
+        }
+
+        let mut res = Vec::new();
+        while !n.is_zero() {
+            let digit;
+            (n, digit) = n.div_rem(base);
+            res.push(self.digits[digit as usize]);
+        }
+        res.reverse();
+        res.into_iter().map(char::from).collect()
+    }
+}
+
+/// An unsigned integer type numerals can be parsed into and formatted from.
+pub trait Numeral: Sized {
+    fn zero() -> Self;
+
+    fn is_zero(&self) -> bool;
+
+    /// `self * base + digit`, or `None` if that overflows.
+    fn checked_mul_add(self, base: u32, digit: u32) -> Option<Self>;
+
+    /// The quotient and remainder of dividing by `base`.
+    fn div_rem(self, base: u32) -> (Self, u32);
+}
+
+macro_rules! impl_numeral {
+    ($($t:ty),*) => {$(
+        impl Numeral for $t {
+            fn zero() -> Self {
+                0
+            }
+
+            fn is_zero(&self) -> bool {
+                *self == 0
+            }
+
+            fn checked_mul_add(self, base: u32, digit: u32) -> Option<Self> {
+                self.checked_mul(<$t>::from(base))?.checked_add(<$t>::from(digit))
+            }
+
+            fn div_rem(self, base: u32) -> (Self, u32) {
+                let base = <$t>::from(base);
+                (self / base, (self % base) as u32)
+            }
+        }
+    )*};
+}
+
+impl_numeral!(u64, u128);
+
+impl Numeral for BigUint {
+    fn zero() -> Self {
+        Self::ZERO
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == Self::ZERO
+    }
+
+    fn checked_mul_add(self, base: u32, digit: u32) -> Option<Self> {
+        Some(self * base + digit)
+    }
+
+    fn div_rem(self, base: u32) -> (Self, u32) {
+        let rem = &self % base;
+        (self / base, rem.try_into().unwrap())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlphabetError {
+    Size(usize),
+    NotAscii(u8),
+    Duplicate(char),
+}
+
+impl Display for AlphabetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Size(size) => write!(f, "an alphabet needs between 2 and 128 digits, not {size}"),
+            Self::NotAscii(digit) => write!(f, "digit {digit:#04x} isn't ASCII"),
+            Self::Duplicate(digit) => write!(f, "digit {digit:?} appears more than once"),
+        }
+    }
+}
+
+impl std::error::Error for AlphabetError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseNumeralError {
+    Base(u32),
+    Empty,
+    Digit { digit: char, base: u32 },
+    Overflow,
+}
+
+impl Display for ParseNumeralError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Base(base) => write!(f, "base {base} isn't supported by this alphabet"),
+            Self::Empty => write!(f, "numeral is empty"),
+            Self::Digit { digit, base } => write!(f, "{digit:?} isn't a digit in base {base}"),
+            Self::Overflow => write!(f, "numeral is too big for the target type"),
+        }
+    }
+}
+
+impl std::error::Error for ParseNumeralError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_width_overflow() {
+        let alphabet = Alphabet::puzzle();
+        assert_eq!(alphabet.parse::<u64>("18446744073709551615", 10), Ok(u64::MAX));
+        assert_eq!(
+            alphabet.parse::<u64>("18446744073709551616", 10),
+            Err(ParseNumeralError::Overflow)
+        );
+        assert_eq!(
+            alphabet.parse::<u64>(&"1".repeat(65), 2),
+            Err(ParseNumeralError::Overflow)
+        );
+        assert_eq!(alphabet.parse::<u128>("18446744073709551616", 10), Ok(1 << 64));
+
+        let max = alphabet.format(u128::MAX, 68);
+        assert_eq!(alphabet.parse::<u128>(&max, 68), Ok(u128::MAX));
+        assert_eq!(
+            alphabet.parse::<u128>(&format!("{max}0"), 68),
+            Err(ParseNumeralError::Overflow)
+        );
+        assert_eq!(alphabet.parse::<u64>(&max, 68), Err(ParseNumeralError::Overflow));
+    }
+
+    #[test]
+    fn big_numerals() {
+        let alphabet = Alphabet::puzzle();
+        let numeral = "^".repeat(40);
+        let n = alphabet.parse::<BigUint>(&numeral, 68).unwrap();
+        assert_eq!(n, BigUint::from(68u32).pow(40) - 1u32);
+        assert_eq!(alphabet.format(n.clone(), 68), numeral);
+        assert_eq!(alphabet.format(n.clone(), 16), n.to_str_radix(16).to_uppercase());
+        assert_eq!(alphabet.parse::<BigUint>(&n.to_str_radix(36).to_uppercase(), 36), Ok(n));
+    }
+
+    #[test]
+    fn zero_and_round_trips() {
+        let alphabet = Alphabet::puzzle();
+        assert_eq!(alphabet.format(0u64, 10), "0");
+        assert_eq!(alphabet.format(BigUint::ZERO, 68), "0");
+        assert_eq!(alphabet.parse::<u64>("000", 2), Ok(0));
+        for base in 2..=68 {
+            for n in [1u64, 67, 68, 12_345_678, u64::MAX] {
+                assert_eq!(alphabet.parse::<u64>(&alphabet.format(n, base), base), Ok(n));
+            }
+        }
+        assert_eq!(alphabet.format(255u64, 16), "FF");
+        assert_eq!(alphabet.format(67u64, 68), "^");
+    }
+
+    #[test]
+    fn invalid_bases_and_digits() {
+        let alphabet = Alphabet::puzzle();
+        assert_eq!(alphabet.parse::<u64>("1", 1), Err(ParseNumeralError::Base(1)));
+        assert_eq!(alphabet.parse::<u64>("1", 69), Err(ParseNumeralError::Base(69)));
+        assert_eq!(alphabet.parse::<u64>("", 10), Err(ParseNumeralError::Empty));
+        assert_eq!(
+            alphabet.parse::<u64>("1A", 10),
+            Err(ParseNumeralError::Digit { digit: 'A', base: 10 })
+        );
+        assert_eq!(
+            alphabet.parse::<u64>("1é", 68),
+            Err(ParseNumeralError::Digit { digit: 'é', base: 68 })
+        );
+        assert_eq!(
+            Alphabet::new(b"01").unwrap().parse::<u64>("2", 2),
+            Err(ParseNumeralError::Digit { digit: '2', base: 2 })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "base 3 isn't supported")]
+    fn format_checks_the_base() {
+        Alphabet::new(b"01").unwrap().format(5u64, 3);
+    }
+
+    #[test]
+    fn invalid_alphabets() {
+        assert_eq!(Alphabet::new(b"0").unwrap_err(), AlphabetError::Size(1));
+        assert_eq!(Alphabet::new(&[b'a'; 129]).unwrap_err(), AlphabetError::Size(129));
+        assert_eq!(Alphabet::new(b"01\xc3").unwrap_err(), AlphabetError::NotAscii(0xc3));
+        assert_eq!(Alphabet::new(b"0120").unwrap_err(), AlphabetError::Duplicate('0'));
+        let ascii = (0..128).collect::<Vec<u8>>();
+        assert_eq!(Alphabet::new(&ascii).unwrap().max_base(), 128);
+    }
+}
@@ -0,0 +1,85 @@
+/// The largest `b` such that `b^k <= n`, computed exactly by binary search.
+///
+/// # Panics
+///
+/// Panics if `k` is zero.
+pub fn floor_root(n: u128, k: u32) -> u128 {
+    assert!(k > 0, "the 0th root is undefined");
+    if k == 1 {
+        return n;
+    }
+
+    // `b^k <= n < 2^128` means `b < 2^(128 / k + 1)`.
+    let (mut lo, mut hi) = (0, 1u128 << (128 / k + 1));
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        if mid.checked_pow(k).is_some_and(|power| power <= n) {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+/// The smallest base in which `n` can be written with at most `digits` digits, i.e. the smallest `b >= 2` such that
+/// `b^digits > n`.
+pub fn min_base_for_digits(n: u128, digits: u32) -> u128 {
+    (floor_root(n, digits) + 1).max(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn floor_root_near_perfect_powers() {
+        for k in 1..=10 {
+            for b in (2..2000u128).chain([u32::MAX as u128, (1 << 42) - 1]) {
+                let Some(power) = b.checked_pow(k) else {
+                    continue;
+                };
+                assert_eq!(floor_root(power - 1, k), b - 1, "{b}^{k} - 1");
+                assert_eq!(floor_root(power, k), b, "{b}^{k}");
+                if let Some(above) = power.checked_add(1) {
+                    assert_eq!(floor_root(above, k), if k == 1 { b + 1 } else { b }, "{b}^{k} + 1");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn floor_root_matches_brute_force() {
+        for k in 1..=6 {
+            let mut b = 0u128;
+            for n in 0..20_000 {
+                while (b + 1).pow(k) <= n {
+                    b += 1;
+                }
+                assert_eq!(floor_root(n, k), b, "{k}th root of {n}");
+            }
+        }
+    }
+
+    #[test]
+    fn floor_root_extremes() {
+        assert_eq!(floor_root(u128::MAX, 1), u128::MAX);
+        assert_eq!(floor_root(u128::MAX, 2), u64::MAX as u128);
+        assert_eq!(floor_root(u128::MAX, 4), u32::MAX as u128);
+        assert_eq!(floor_root(u128::MAX, 128), 1);
+        assert_eq!(floor_root(0, 3), 0);
+    }
+
+    #[test]
+    fn min_base_at_fourth_powers() {
+        for b in 2..5000u128 {
+            let power = b.pow(4);
+            // `b^4` is `10000` in base `b`, five digits, so it needs one more base than `ceil(b^4^(1/4)) = b`.
+            assert_eq!(min_base_for_digits(power, 4), b + 1);
+            assert_eq!(min_base_for_digits(power - 1, 4), b);
+            assert_eq!(min_base_for_digits(power + 1, 4), b + 1);
+        }
+        assert_eq!(min_base_for_digits(0, 4), 2);
+        assert_eq!(min_base_for_digits(1, 4), 2);
+    }
+}
@@ -0,0 +1,81 @@
+use std::{collections::HashSet, fmt::Write};
+
+use crate::route_graph::RouteGraph;
+
+/// A set of nodes and edges to draw in one colour.
+#[derive(Debug, Clone)]
+pub struct Highlight<'h> {
+    pub label: &'h str,
+    pub colour: &'h str,
+    pub nodes: HashSet<usize>,
+    pub edges: HashSet<(usize, usize)>,
+}
+
+impl RouteGraph<'_> {
+    /// Render the graph in Graphviz DOT format, labelling every edge with its weight.
+    ///
+    /// Nodes and edges that belong to several highlights get every matching colour.
+    pub fn to_dot(&self, highlights: &[Highlight]) -> String {
+        let mut dot = String::from("digraph {\n");
+
+        for highlight in highlights {
+            writeln!(dot, "    // {}: {}", highlight.colour, highlight.label).unwrap();
+        }
+
+        for node in 0..self.len() {
+            let colours = highlights
+                .iter()
+                .filter(|h| h.nodes.contains(&node))
+                .map(|h| h.colour)
+                .collect::<Vec<_>>();
+
+            write!(dot, "    {}", quoted(self.name(node))).unwrap();
+            if !colours.is_empty() {
+                write!(dot, " [style=wedged, fillcolor=\"{}\"]", colours.join(":")).unwrap();
+            }
+            dot.push('\n');
+        }
+
+        for (from, to, weight) in self.edges() {
+            let colours = highlights
+                .iter()
+                .filter(|h| h.edges.contains(&(from, to)))
+                .map(|h| h.colour)
+                .collect::<Vec<_>>();
+
+            write!(
+                dot,
+                "    {} -> {} [label=\"{weight}\"",
+                quoted(self.name(from)),
+                quoted(self.name(to))
+            )
+            .unwrap();
+            if !colours.is_empty() {
+                write!(dot, ", color=\"{}\"", colours.join(":")).unwrap();
+            }
+            dot.push_str("]\n");
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// A node name as a quoted DOT id, so names that aren't plain identifiers still parse.
+fn quoted(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_are_quoted() {
+        let graph = RouteGraph::parse("North Gate -> 2nd-Pier | 5\n2nd-Pier -> say \"hi\" | 1\n").unwrap();
+        let dot = graph.to_dot(&[]);
+        assert!(dot.contains("    \"North Gate\"\n"));
+        assert!(dot.contains("    \"North Gate\" -> \"2nd-Pier\" [label=\"5\"]\n"));
+        assert!(dot.contains("    \"2nd-Pier\" -> \"say \\\"hi\\\"\" [label=\"1\"]\n"));
+    }
+}
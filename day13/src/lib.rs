@@ -0,0 +1,3 @@
+pub mod dot;
+pub mod report;
+pub mod route_graph;
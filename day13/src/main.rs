@@ -1,60 +1,58 @@
-use std::collections::{HashMap, HashSet};
-
-use pathfinding::prelude::*;
-
-fn longest_cycle_starting_from(map: &HashMap<&str, Vec<(&str, u64)>>, start: &str) -> u64 {
-    let mut q = vec![(start, 0, HashSet::new())];
-
-    let mut solution = u64::MIN;
-
-    while let Some((current, cost, visited)) = q.pop() {
-        for (next, next_cost) in map.get(current).unwrap() {
-            if *next == start {
-                solution = solution.max(cost + next_cost);
-                continue;
-            }
-
-            let mut next_visited = visited.clone();
-            if next_visited.insert(next) {
-                q.push((next, cost + next_cost, next_visited));
-            }
-        }
+use day13::{dot::Highlight, report::ShortestPathReport, route_graph::RouteGraph};
+
+fn tree_highlight<'h>(label: &'h str, colour: &'h str, report: &ShortestPathReport) -> Highlight<'h> {
+    Highlight {
+        label,
+        colour,
+        nodes: report
+            .routes
+            .iter()
+            .flat_map(|route| route.nodes.iter().copied())
+            .collect(),
+        edges: report
+            .routes
+            .iter()
+            .flat_map(|route| route.nodes.windows(2).map(|w| (w[0], w[1])))
+            .collect(),
     }
-
-    solution
 }
 
 fn main() {
     let input = include_str!("input.txt");
+    let graph = RouteGraph::parse(input).unwrap();
+    let start = graph.index_of("STT").unwrap();
+
+    let part1_report = ShortestPathReport::new(&graph, start, |_| 1);
+    let part2_report = ShortestPathReport::new(&graph, start, |weight| weight);
+    let cycle = graph.longest_cycle().unwrap();
+
+    // `cargo run -p day13 -- --dot > day13/src/input.txt.dot` regenerates the visualisation.
+    if std::env::args().any(|arg| arg == "--dot") {
+        let cycle_highlight = Highlight {
+            label: "part 3 longest cycle",
+            colour: "green",
+            nodes: cycle.nodes.iter().copied().collect(),
+            edges: (0..cycle.nodes.len())
+                .map(|i| (cycle.nodes[i], cycle.nodes[(i + 1) % cycle.nodes.len()]))
+                .collect(),
+        };
+        print!(
+            "{}",
+            graph.to_dot(&[
+                tree_highlight("part 1 shortest-path tree", "red", &part1_report),
+                tree_highlight("part 2 shortest-path tree", "blue", &part2_report),
+                cycle_highlight,
+            ])
+        );
+        return;
+    }
 
-    let mut paths = HashMap::new();
-    input.lines().for_each(|line| {
-        let (edge, weight) = line.split_once(" | ").unwrap();
-        let (from, to) = edge.split_once(" -> ").unwrap();
-        paths
-            .entry(from)
-            .or_insert_with(Vec::new)
-            .push((to, weight.parse::<u64>().unwrap()));
-    });
-
-    let part1_paths = dijkstra_all(&"STT", |current| {
-        paths.get(current).unwrap().iter().map(|(next, _)| (*next, 1))
-    });
-    let mut part1_costs = part1_paths.values().map(|(_, c)| c).collect::<Vec<_>>();
-    part1_costs.sort_unstable();
-    let part1 = part1_costs.into_iter().rev().take(3).product::<u64>();
-    println!("{part1}");
-
-    let part2_paths = dijkstra_all(&"STT", |current| paths.get(current).unwrap().iter().copied());
-    let mut part2_costs = part2_paths.values().map(|(_, c)| c).collect::<Vec<_>>();
-    part2_costs.sort_unstable();
-    let part2 = part2_costs.into_iter().rev().take(3).product::<u64>();
-    println!("{part2}");
+    if std::env::args().any(|arg| arg == "--report") {
+        print!("{part1_report}\n{part2_report}");
+        return;
+    }
 
-    let part3 = paths
-        .keys()
-        .map(|node| longest_cycle_starting_from(&paths, node))
-        .max()
-        .unwrap();
-    println!("{part3}");
+    println!("{}", part1_report.product_of_farthest(3));
+    println!("{}", part2_report.product_of_farthest(3));
+    println!("{}", cycle.weight);
 }
@@ -0,0 +1,195 @@
+use std::fmt::Display;
+
+use pathfinding::prelude::{build_path, dijkstra_all};
+
+use crate::route_graph::RouteGraph;
+
+/// The shortest route from the report's start to one destination.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route<'a> {
+    pub destination: &'a str,
+    pub distance: u64,
+    pub hops: usize,
+    /// The names of the nodes along the route, from the start to the destination.
+    pub path: Vec<&'a str>,
+    /// The same nodes as `path`, as indices into the graph.
+    pub nodes: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Distance,
+    Hops,
+    Destination,
+}
+
+/// Shortest routes from one node to every other node, plus the nodes that can't be reached at all.
+#[derive(Debug, Clone)]
+pub struct ShortestPathReport<'a> {
+    pub start: &'a str,
+    pub routes: Vec<Route<'a>>,
+    pub unreachable: Vec<&'a str>,
+}
+
+impl<'a> ShortestPathReport<'a> {
+    /// Compute every shortest route from `start`, pricing each edge by passing its weight through `edge_cost`.
+    pub fn new(graph: &RouteGraph<'a>, start: usize, edge_cost: impl Fn(u64) -> u64) -> Self {
+        let edge_cost = &edge_cost;
+        let parents = dijkstra_all(&start, move |&node| {
+            graph
+                .successors(node)
+                .iter()
+                .map(move |&(next, weight)| (next, edge_cost(weight)))
+        });
+
+        let mut routes = Vec::with_capacity(parents.len());
+        let mut unreachable = Vec::new();
+        for node in (0..graph.len()).filter(|&node| node != start) {
+            let Some(&(_, distance)) = parents.get(&node) else {
+                unreachable.push(graph.name(node));
+                continue;
+            };
+
+            let nodes = build_path(&node, &parents);
+            routes.push(Route {
+                destination: graph.name(node),
+                distance,
+                hops: nodes.len() - 1,
+                path: nodes.iter().map(|&n| graph.name(n)).collect(),
+                nodes,
+            });
+        }
+        unreachable.sort_unstable();
+
+        let mut report = Self {
+            start: graph.name(start),
+            routes,
+            unreachable,
+        };
+        report.sort_by(SortKey::Destination);
+        report
+    }
+
+    /// Sort the routes in ascending order of `key`, breaking ties by destination name.
+    pub fn sort_by(&mut self, key: SortKey) {
+        self.routes.sort_by(|a, b| {
+            let primary = match key {
+                SortKey::Distance => a.distance.cmp(&b.distance),
+                SortKey::Hops => a.hops.cmp(&b.hops),
+                SortKey::Destination => std::cmp::Ordering::Equal,
+            };
+            primary.then_with(|| a.destination.cmp(b.destination))
+        });
+    }
+
+    /// Keep only the routes matching `predicate`.
+    pub fn retain(&mut self, predicate: impl FnMut(&Route<'a>) -> bool) {
+        self.routes.retain(predicate);
+    }
+
+    /// The product of the `n` largest distances.
+    pub fn product_of_farthest(&self, n: usize) -> u64 {
+        let mut distances = self.routes.iter().map(|route| route.distance).collect::<Vec<_>>();
+        distances.sort_unstable();
+        distances.into_iter().rev().take(n).product()
+    }
+}
+
+impl Display for ShortestPathReport<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "from {}:", self.start)?;
+        for route in &self.routes {
+            writeln!(
+                f,
+                "  {:<8} distance {:>5}  hops {:>3}  {}",
+                route.destination,
+                route.distance,
+                route.hops,
+                route.path.join(" -> ")
+            )?;
+        }
+
+        if !self.unreachable.is_empty() {
+            writeln!(f, "unreachable:")?;
+            for node in &self.unreachable {
+                writeln!(f, "  {node}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `T` is a sink, and nothing from `S` leads to `U` or `V`.
+    const INPUT: &str =
+        "S -> A | 4\nS -> B | 1\nB -> A | 1\nA -> T | 1\nB -> C | 5\nC -> S | 2\nU -> S | 1\nU -> V | 1\n";
+
+    fn destinations<'a>(report: &ShortestPathReport<'a>) -> Vec<&'a str> {
+        report.routes.iter().map(|route| route.destination).collect()
+    }
+
+    #[test]
+    fn routes_and_unreachable_nodes() {
+        let graph = RouteGraph::parse(INPUT).unwrap();
+        let report = ShortestPathReport::new(&graph, graph.index_of("S").unwrap(), |weight| weight);
+        assert_eq!(report.start, "S");
+        assert_eq!(report.unreachable, ["U", "V"]);
+        assert_eq!(destinations(&report), ["A", "B", "C", "T"]);
+
+        let expected = [
+            ("A", 2, vec!["S", "B", "A"]),
+            ("B", 1, vec!["S", "B"]),
+            ("C", 6, vec!["S", "B", "C"]),
+            ("T", 3, vec!["S", "B", "A", "T"]),
+        ];
+        for (route, (destination, distance, path)) in report.routes.iter().zip(expected) {
+            assert_eq!(route.destination, destination);
+            assert_eq!(route.distance, distance);
+            assert_eq!(route.hops, path.len() - 1);
+            assert_eq!(route.path, path);
+            let names = route.nodes.iter().map(|&node| graph.name(node)).collect::<Vec<_>>();
+            assert_eq!(names, path);
+        }
+        assert_eq!(report.product_of_farthest(2), 18);
+    }
+
+    #[test]
+    fn edge_cost_changes_the_routes() {
+        let graph = RouteGraph::parse(INPUT).unwrap();
+        let report = ShortestPathReport::new(&graph, graph.index_of("S").unwrap(), |_| 1);
+        let a = report.routes.iter().find(|route| route.destination == "A").unwrap();
+        assert_eq!((a.distance, a.hops), (1, 1));
+        assert_eq!(a.path, ["S", "A"]);
+    }
+
+    #[test]
+    fn sink_start_reaches_nothing() {
+        let graph = RouteGraph::parse(INPUT).unwrap();
+        let report = ShortestPathReport::new(&graph, graph.index_of("T").unwrap(), |weight| weight);
+        assert!(report.routes.is_empty());
+        assert_eq!(report.unreachable, ["A", "B", "C", "S", "U", "V"]);
+        assert_eq!(report.product_of_farthest(3), 1);
+    }
+
+    #[test]
+    fn sort_and_retain() {
+        let graph = RouteGraph::parse(INPUT).unwrap();
+        let mut report = ShortestPathReport::new(&graph, graph.index_of("S").unwrap(), |weight| weight);
+
+        report.sort_by(SortKey::Distance);
+        assert_eq!(destinations(&report), ["B", "A", "T", "C"]);
+        report.sort_by(SortKey::Hops);
+        assert_eq!(destinations(&report), ["B", "A", "C", "T"]);
+        report.sort_by(SortKey::Destination);
+        assert_eq!(destinations(&report), ["A", "B", "C", "T"]);
+
+        report.sort_by(SortKey::Distance);
+        report.retain(|route| route.hops == 2);
+        assert_eq!(destinations(&report), ["A", "C"]);
+        assert_eq!(report.unreachable, ["U", "V"]);
+    }
+}
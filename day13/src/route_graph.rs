@@ -0,0 +1,396 @@
+use std::{collections::HashMap, fmt::Display};
+
+use pathfinding::prelude::strongly_connected_components;
+
+/// Components up to this many nodes get their longest cycle from an exact bitmask DP; bigger ones fall back to a
+/// branch-and-bound search.
+const DP_MAX_NODES: usize = 16;
+
+/// A weighted directed graph whose nodes are named by the strings in its edge list.
+#[derive(Debug, Clone, Default)]
+pub struct RouteGraph<'a> {
+    names: Vec<&'a str>,
+    indices: HashMap<&'a str, usize>,
+    successors: Vec<Vec<(usize, u64)>>,
+}
+
+/// A simple cycle, listing each of its nodes once starting from an arbitrary one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    pub weight: u64,
+    pub nodes: Vec<usize>,
+}
+
+#[derive(Debug)]
+pub enum ParseRouteError {
+    MissingWeight { line: usize },
+    MissingArrow { line: usize },
+    Weight { line: usize, err: std::num::ParseIntError },
+}
+
+impl Display for ParseRouteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingWeight { line } => write!(f, "line {line}: expected `A -> B | weight`, found no ` | `"),
+            Self::MissingArrow { line } => write!(f, "line {line}: expected `A -> B | weight`, found no ` -> `"),
+            Self::Weight { line, err } => write!(f, "line {line}: invalid weight: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseRouteError {}
+
+impl<'a> RouteGraph<'a> {
+    /// Parse an edge list with one `A -> B | weight` edge per line.
+    pub fn parse(input: &'a str) -> Result<Self, ParseRouteError> {
+        let mut graph = Self::default();
+
+        for (line, edge) in input.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+            let line = line + 1;
+            let (edge, weight) = edge.split_once(" | ").ok_or(ParseRouteError::MissingWeight { line })?;
+            let (from, to) = edge.split_once(" -> ").ok_or(ParseRouteError::MissingArrow { line })?;
+            let weight = weight
+                .trim()
+                .parse()
+                .map_err(|err| ParseRouteError::Weight { line, err })?;
+
+            let from = graph.add_node(from.trim());
+            let to = graph.add_node(to.trim());
+            graph.successors[from].push((to, weight));
+        }
+
+        Ok(graph)
+    }
+
+    fn add_node(&mut self, name: &'a str) -> usize {
+        *self.indices.entry(name).or_insert_with(|| {
+            self.names.push(name);
+            self.successors.push(Vec::new());
+            self.names.len() - 1
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn name(&self, node: usize) -> &'a str {
+        self.names[node]
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.indices.get(name).copied()
+    }
+
+    /// The outgoing edges of `node` as `(target, weight)` pairs.
+    pub fn successors(&self, node: usize) -> &[(usize, u64)] {
+        &self.successors[node]
+    }
+
+    /// Every edge as a `(from, to, weight)` triple.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize, u64)> + '_ {
+        self.successors
+            .iter()
+            .enumerate()
+            .flat_map(|(from, edges)| edges.iter().map(move |&(to, weight)| (from, to, weight)))
+    }
+
+    /// Find the heaviest simple cycle, or `None` if the graph is acyclic.
+    ///
+    /// A cycle can't leave its strongly connected component, so each component is solved on its own.
+    pub fn longest_cycle(&self) -> Option<Cycle> {
+        let nodes = (0..self.len()).collect::<Vec<_>>();
+        strongly_connected_components(&nodes, |&node| self.successors[node].iter().map(|&(next, _)| next))
+            .into_iter()
+            .filter_map(|component| self.longest_cycle_in(&component))
+            .max_by_key(|cycle| cycle.weight)
+    }
+
+    fn longest_cycle_in(&self, component: &[usize]) -> Option<Cycle> {
+        let mut local = vec![usize::MAX; self.len()];
+        for (i, &node) in component.iter().enumerate() {
+            local[node] = i;
+        }
+
+        let successors = component
+            .iter()
+            .map(|&node| {
+                let mut edges = self.successors[node]
+                    .iter()
+                    .filter(|&&(next, _)| local[next] != usize::MAX)
+                    .map(|&(next, weight)| (local[next], weight))
+                    .collect::<Vec<_>>();
+                // Trying heavy edges first finds good cycles early, which makes the search's pruning bite sooner.
+                edges.sort_unstable_by_key(|&(_, weight)| std::cmp::Reverse(weight));
+                edges
+            })
+            .collect::<Vec<_>>();
+
+        // Every cycle is found exactly once, from its lowest-numbered node, by never stepping to a lower one.
+        let best = if component.len() <= DP_MAX_NODES {
+            (0..component.len())
+                .filter_map(|start| dp_cycle_through(&successors, start))
+                .max_by_key(|cycle| cycle.weight)
+        } else {
+            let mut search = CycleSearch::new(&successors);
+            (0..component.len()).for_each(|start| search.run(start));
+            search.best
+        };
+
+        best.map(|Cycle { weight, nodes }| Cycle {
+            weight,
+            nodes: nodes.into_iter().map(|i| component[i]).collect(),
+        })
+    }
+}
+
+/// Heaviest cycle through `start` using only nodes numbered above it, by DP over the set of nodes visited so far.
+fn dp_cycle_through(successors: &[Vec<(usize, u64)>], start: usize) -> Option<Cycle> {
+    let offset = start + 1;
+    let bits = successors.len() - offset;
+
+    // `best[mask * bits + last]` is the heaviest path from `start` through exactly `mask`, ending at `last`.
+    let mut best = vec![None::<(u64, usize)>; (1 << bits) * bits.max(1)];
+    let mut cycle = None::<(u64, usize, usize)>;
+
+    for &(next, weight) in &successors[start] {
+        if next == start {
+            cycle = cycle.max(Some((weight, 0, usize::MAX)));
+        } else if next > start {
+            let slot = &mut best[(1 << (next - offset)) * bits + next - offset];
+            *slot = (*slot).max(Some((weight, usize::MAX)));
+        }
+    }
+
+    for mask in 1..1usize << bits {
+        for last in (0..bits).filter(|&last| mask & (1 << last) != 0) {
+            let Some((weight, _)) = best[mask * bits + last] else {
+                continue;
+            };
+
+            for &(next, step) in &successors[last + offset] {
+                if next == start {
+                    if cycle.is_none_or(|(w, _, _)| weight + step > w) {
+                        cycle = Some((weight + step, mask, last));
+                    }
+                } else if next > start && mask & (1 << (next - offset)) == 0 {
+                    let slot = &mut best[(mask | 1 << (next - offset)) * bits + next - offset];
+                    if slot.is_none_or(|(w, _)| weight + step > w) {
+                        *slot = Some((weight + step, last));
+                    }
+                }
+            }
+        }
+    }
+
+    let (weight, mut mask, mut last) = cycle?;
+    let mut nodes = vec![];
+    while last != usize::MAX {
+        nodes.push(last + offset);
+        let (_, prev) = best[mask * bits + last].unwrap();
+        mask &= !(1 << last);
+        last = prev;
+    }
+    nodes.push(start);
+    nodes.reverse();
+
+    Some(Cycle { weight, nodes })
+}
+
+/// Depth-first search over simple paths, pruned whenever even the heaviest possible way of finishing the cycle can't
+/// beat the best one found so far.
+struct CycleSearch<'g> {
+    successors: &'g [Vec<(usize, u64)>],
+    heaviest_out: Vec<u64>,
+    path: Vec<usize>,
+    visited: Vec<bool>,
+    best: Option<Cycle>,
+}
+
+impl<'g> CycleSearch<'g> {
+    fn new(successors: &'g [Vec<(usize, u64)>]) -> Self {
+        let heaviest_out = successors
+            .iter()
+            .map(|edges| edges.iter().map(|&(_, w)| w).max().unwrap_or(0))
+            .collect();
+        Self {
+            successors,
+            heaviest_out,
+            path: Vec::new(),
+            visited: vec![false; successors.len()],
+            best: None,
+        }
+    }
+
+    fn run(&mut self, start: usize) {
+        // Each node still to be visited can add at most its heaviest outgoing edge to the cycle.
+        let slack = self.heaviest_out[start..].iter().sum();
+        self.path.push(start);
+        self.visit(start, start, 0, slack);
+        self.path.pop();
+    }
+
+    fn visit(&mut self, start: usize, node: usize, weight: u64, slack: u64) {
+        let slack = slack - self.heaviest_out[node];
+        for &(next, step) in self.successors[node].iter() {
+            if next == start {
+                if self.best.as_ref().is_none_or(|best| weight + step > best.weight) {
+                    self.best = Some(Cycle {
+                        weight: weight + step,
+                        nodes: self.path.clone(),
+                    });
+                }
+            } else if next > start && !self.visited[next] {
+                let bound = weight + step + slack;
+                if self.best.as_ref().is_some_and(|best| bound <= best.weight) {
+                    continue;
+                }
+
+                self.path.push(next);
+                self.visited[next] = true;
+                self.visit(start, next, weight + step, slack);
+                self.visited[next] = false;
+                self.path.pop();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    fn random_edges(rng: &mut StdRng, nodes: u64, edges: u64) -> String {
+        (0..edges)
+            .map(|_| {
+                format!(
+                    "N{} -> N{} | {}\n",
+                    rng.gen_range(0..nodes),
+                    rng.gen_range(0..nodes),
+                    rng.gen_range(1..=20)
+                )
+            })
+            .collect()
+    }
+
+    /// Try every simple path from every node, without any pruning.
+    fn brute_force(graph: &RouteGraph) -> Option<u64> {
+        fn extend(graph: &RouteGraph, start: usize, node: usize, weight: u64, on_path: &mut Vec<bool>) -> Option<u64> {
+            let mut best = None;
+            for &(next, step) in graph.successors(node) {
+                if next == start {
+                    best = best.max(Some(weight + step));
+                } else if next > start && !on_path[next] {
+                    on_path[next] = true;
+                    best = best.max(extend(graph, start, next, weight + step, on_path));
+                    on_path[next] = false;
+                }
+            }
+            best
+        }
+
+        (0..graph.len())
+            .filter_map(|start| extend(graph, start, start, 0, &mut vec![false; graph.len()]))
+            .max()
+    }
+
+    fn assert_is_cycle(graph: &RouteGraph, cycle: &Cycle) {
+        let mut nodes = cycle.nodes.clone();
+        nodes.sort_unstable();
+        nodes.dedup();
+        assert_eq!(nodes.len(), cycle.nodes.len(), "cycle repeats a node");
+
+        let weight = (0..cycle.nodes.len())
+            .map(|i| {
+                let (from, to) = (cycle.nodes[i], cycle.nodes[(i + 1) % cycle.nodes.len()]);
+                graph
+                    .successors(from)
+                    .iter()
+                    .filter(|&&(next, _)| next == to)
+                    .map(|&(_, w)| w)
+                    .max()
+                    .unwrap()
+            })
+            .sum::<u64>();
+        assert_eq!(weight, cycle.weight);
+    }
+
+    #[test]
+    fn longest_cycle_matches_brute_force() {
+        let mut rng = StdRng::seed_from_u64(0x853c_49e6_748f_ea9b);
+        // Up to 24 nodes, so both the DP and the branch-and-bound search get used.
+        for _ in 0..300 {
+            let nodes = rng.gen_range(1..=24);
+            let edges = rng.gen_range(0..nodes * 2 + 1);
+            let input = random_edges(&mut rng, nodes, edges);
+            let graph = RouteGraph::parse(&input).unwrap();
+
+            let cycle = graph.longest_cycle();
+            assert_eq!(cycle.as_ref().map(|c| c.weight), brute_force(&graph), "{input}");
+            if let Some(cycle) = &cycle {
+                assert_is_cycle(&graph, cycle);
+            }
+        }
+    }
+
+    #[test]
+    fn branch_and_bound_matches_brute_force() {
+        let mut rng = StdRng::seed_from_u64(0x2f8b_1c6e_5d3a_9047);
+        for _ in 0..50 {
+            // A ring through every node keeps them all in one component, too big for the DP.
+            let nodes = DP_MAX_NODES as u64 + rng.gen_range(1..=6);
+            let ring = (0..nodes)
+                .map(|i| format!("N{i} -> N{} | {}\n", (i + 1) % nodes, rng.gen_range(1..=20)))
+                .collect::<String>();
+            let chords = rng.gen_range(0..nodes);
+            let input = ring + &random_edges(&mut rng, nodes, chords);
+            let graph = RouteGraph::parse(&input).unwrap();
+
+            let cycle = graph.longest_cycle().unwrap();
+            assert_eq!(Some(cycle.weight), brute_force(&graph), "{input}");
+            assert_is_cycle(&graph, &cycle);
+        }
+    }
+
+    #[test]
+    fn dp_and_search_agree() {
+        let mut rng = StdRng::seed_from_u64(0xda3e_39cb_94b9_5bdb);
+        for _ in 0..300 {
+            let nodes = rng.gen_range(1..=DP_MAX_NODES as u64);
+            let edges = rng.gen_range(0..nodes * 3 + 1);
+            let input = random_edges(&mut rng, nodes, edges);
+            let graph = RouteGraph::parse(&input).unwrap();
+
+            let dp = (0..graph.len())
+                .filter_map(|start| dp_cycle_through(&graph.successors, start))
+                .map(|cycle| cycle.weight)
+                .max();
+            let mut search = CycleSearch::new(&graph.successors);
+            (0..graph.len()).for_each(|start| search.run(start));
+            assert_eq!(dp, search.best.map(|cycle| cycle.weight), "{input}");
+        }
+    }
+
+    #[test]
+    fn component_bigger_than_128_nodes() {
+        let ring = (0..200)
+            .map(|i| format!("N{i} -> N{} | 1\n", (i + 1) % 200))
+            .collect::<String>();
+        let graph = RouteGraph::parse(&ring).unwrap();
+        let cycle = graph.longest_cycle().unwrap();
+        assert_eq!(cycle.weight, 200);
+        assert_is_cycle(&graph, &cycle);
+    }
+
+    #[test]
+    fn acyclic_graph_has_no_cycle() {
+        let graph = RouteGraph::parse("A -> B | 1\nB -> C | 2\nA -> C | 3\n").unwrap();
+        assert_eq!(graph.longest_cycle(), None);
+    }
+}
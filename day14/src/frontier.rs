@@ -0,0 +1,146 @@
+use std::fmt::Write;
+
+use crate::{
+    item::{Item, Value},
+    knapsack::{best_by_budget, Entry},
+};
+
+/// A point on the quality/cost Pareto frontier: the cheapest way to reach its quality.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrontierPoint {
+    pub cost: usize,
+    pub value: Value,
+}
+
+/// The best set of items for every budget from 0 to some maximum, all computed in a single knapsack pass.
+#[derive(Debug, Clone)]
+pub struct Frontier {
+    best: Vec<Value>,
+}
+
+impl Frontier {
+    pub fn new(items: &[Item], max_budget: usize) -> Self {
+        let entries = items
+            .iter()
+            .map(|item| Entry::once(item.cost, Value::from(item)))
+            .collect::<Vec<_>>();
+
+        Self {
+            best: best_by_budget(&entries, max_budget),
+        }
+    }
+
+    pub fn max_budget(&self) -> usize {
+        self.best.len() - 1
+    }
+
+    /// The best value that can be bought with at most `budget`.
+    pub fn best(&self, budget: usize) -> Value {
+        self.best[budget]
+    }
+
+    /// The budgets at which quality goes up, as no smaller budget can reach the same quality.
+    pub fn points(&self) -> impl Iterator<Item = FrontierPoint> + '_ {
+        self.best
+            .iter()
+            .enumerate()
+            .filter(|&(cost, value)| cost == 0 || value.quality > self.best[cost - 1].quality)
+            .map(|(cost, &value)| FrontierPoint { cost, value })
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("cost,quality,unique_materials\n");
+        for point in self.points() {
+            writeln!(
+                csv,
+                "{},{},{}",
+                point.cost, point.value.quality, point.value.unique_materials.0
+            )
+            .unwrap();
+        }
+        csv
+    }
+
+    pub fn to_json(&self) -> String {
+        let points = self
+            .points()
+            .map(|point| {
+                format!(
+                    r#"{{"cost":{},"quality":{},"unique_materials":{}}}"#,
+                    point.cost, point.value.quality, point.value.unique_materials.0
+                )
+            })
+            .collect::<Vec<_>>();
+        format!("[{}]\n", points.join(","))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::knapsack::solve;
+
+    /// The cost and value of every subset of `items`.
+    fn subsets(items: &[Item]) -> Vec<(usize, Value)> {
+        (0..1u32 << items.len())
+            .map(|subset| {
+                items
+                    .iter()
+                    .enumerate()
+                    .filter(|&(i, _)| subset & (1 << i) != 0)
+                    .fold((0, Value::default()), |(cost, value), (_, item)| {
+                        (cost + item.cost, value + Value::from(item))
+                    })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let mut rng = StdRng::seed_from_u64(0x428a_2f98_d728_ae22);
+        for _ in 0..200 {
+            let items = (0..rng.gen_range(0..9))
+                .map(|_| Item {
+                    quality: rng.gen_range(0..10),
+                    cost: rng.gen_range(0..12),
+                    unique_materials: rng.gen_range(0..5),
+                })
+                .collect::<Vec<_>>();
+            let max_budget = rng.gen_range(0..40);
+            let frontier = Frontier::new(&items, max_budget);
+            let subsets = subsets(&items);
+            assert_eq!(frontier.max_budget(), max_budget);
+
+            let entries = items
+                .iter()
+                .map(|item| Entry::once(item.cost, Value::from(item)))
+                .collect::<Vec<_>>();
+            for budget in 0..=max_budget {
+                let best = subsets
+                    .iter()
+                    .filter(|&&(cost, _)| cost <= budget)
+                    .map(|&(_, value)| value)
+                    .max();
+                assert_eq!(Some(frontier.best(budget)), best);
+                assert_eq!(frontier.best(budget), solve(&entries, budget).value);
+            }
+
+            let points = frontier.points().collect::<Vec<_>>();
+            assert_eq!(points.first().map(|point| point.cost), Some(0));
+            for point in &points {
+                assert_eq!(point.value, frontier.best(point.cost));
+                let cheapest = subsets
+                    .iter()
+                    .filter(|&&(_, value)| value.quality >= point.value.quality)
+                    .map(|&(cost, _)| cost)
+                    .min();
+                assert_eq!(Some(point.cost), cheapest);
+            }
+            for pair in points.windows(2) {
+                assert!(pair[0].value.quality < pair[1].value.quality);
+            }
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use std::{cmp::Reverse, ops::Add};
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Item {
+    pub quality: usize,
+    pub cost: usize,
+    pub unique_materials: usize,
+}
+
+/// Parse one item per line, formatted as `N CODE | Quality : q, Cost : c, Unique Materials : u`.
+pub fn parse_items(input: &str) -> Vec<Item> {
+    input
+        .lines()
+        .map(|line| {
+            let mut it = line
+                .split_once(" | ")
+                .unwrap()
+                .1
+                .split(", ")
+                .map(|part| part.split_once(": ").unwrap().1.parse().unwrap());
+
+            (it.next().unwrap(), it.next().unwrap(), it.next().unwrap())
+        })
+        .map(|(quality, cost, unique_materials)| Item {
+            quality,
+            cost,
+            unique_materials,
+        })
+        .collect()
+}
+
+/// How good a set of items is: the most total quality, then the fewest total unique materials.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Debug)]
+pub struct Value {
+    pub quality: usize,
+    pub unique_materials: Reverse<usize>,
+}
+
+impl Value {
+    /// The puzzle's score for a set of items.
+    pub fn score(self) -> usize {
+        self.quality * self.unique_materials.0
+    }
+}
+
+impl Add for Value {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            quality: self.quality + rhs.quality,
+            unique_materials: Reverse(self.unique_materials.0 + rhs.unique_materials.0),
+        }
+    }
+}
+
+impl From<&Item> for Value {
+    fn from(item: &Item) -> Self {
+        Self {
+            quality: item.quality,
+            unique_materials: Reverse(item.unique_materials),
+        }
+    }
+}
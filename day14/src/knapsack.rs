@@ -0,0 +1,523 @@
+use std::{mem::swap, ops::Add};
+
+/// How many copies of an item may go into the knapsack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Copies {
+    Bounded(usize),
+    Unbounded,
+}
+
+/// Something that can go into the knapsack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry<V> {
+    pub cost: usize,
+    pub value: V,
+    pub copies: Copies,
+}
+
+impl<V> Entry<V> {
+    /// An item that can be picked at most once.
+    pub fn once(cost: usize, value: V) -> Self {
+        Self {
+            cost,
+            value,
+            copies: Copies::Bounded(1),
+        }
+    }
+}
+
+/// The best way to fill the knapsack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution<V> {
+    pub value: V,
+    pub cost: usize,
+    /// How many copies of each entry were picked, in the same order as the entries.
+    pub counts: Vec<usize>,
+}
+
+/// One row of the DP table: `count` copies of `entry`, taken together or not at all.
+#[derive(Debug, Clone, Copy)]
+struct Bundle<V> {
+    entry: usize,
+    count: usize,
+    cost: usize,
+    value: V,
+    unbounded: bool,
+}
+
+/// `value` added to itself until there are `count` copies of it, in O(log `count`) additions.
+fn repeat<V: Add<Output = V> + Copy>(value: V, mut count: usize) -> V {
+    assert!(count > 0, "can't repeat a value zero times");
+    let (mut total, mut power) = (None, value);
+    loop {
+        if count & 1 == 1 {
+            total = Some(total.map_or(power, |total| total + power));
+        }
+        count >>= 1;
+        if count == 0 {
+            return total.unwrap();
+        }
+        power = power + power;
+    }
+}
+
+/// Pick the entries that maximise the total value without spending more than `budget`.
+///
+/// `V::default()` must be the value of an empty knapsack, and values are compared with `Ord`, so a lexicographic
+/// value type gives lexicographic tie-breaking for free.
+///
+/// # Panics
+///
+/// Panics if an unbounded entry costs nothing, as there's no limit on how many copies of it would fit.
+pub fn solve<V>(entries: &[Entry<V>], budget: usize) -> Solution<V>
+where
+    V: Ord + Add<Output = V> + Copy + Default,
+{
+    let bundles = split_into_bundles(entries, budget);
+
+    // `table[i][b]` is the best value using only the first `i` bundles with at most `b` spent.
+    let width = budget + 1;
+    let mut table = vec![V::default(); (bundles.len() + 1) * width];
+    for (i, bundle) in bundles.iter().enumerate() {
+        let (done, rest) = table.split_at_mut((i + 1) * width);
+        fill_row(bundle, &done[i * width..], &mut rest[..width]);
+    }
+
+    let mut counts = vec![0; entries.len()];
+    let mut cost = 0;
+    let (mut i, mut b) = (bundles.len(), budget);
+    while i > 0 {
+        let bundle = &bundles[i - 1];
+        if table[i * width + b] == table[(i - 1) * width + b] {
+            i -= 1;
+            continue;
+        }
+
+        counts[bundle.entry] += bundle.count;
+        cost += bundle.cost;
+        b -= bundle.cost;
+        if !bundle.unbounded {
+            i -= 1;
+        }
+    }
+
+    Solution {
+        value: table[bundles.len() * width + budget],
+        cost,
+        counts,
+    }
+}
+
+/// The best value for every budget from 0 to `max_budget`, each computed as by [`solve`].
+///
+/// This only keeps two rows of the DP table around, so it can't tell which entries were picked.
+pub fn best_by_budget<V>(entries: &[Entry<V>], max_budget: usize) -> Vec<V>
+where
+    V: Ord + Add<Output = V> + Copy + Default,
+{
+    let mut prev = vec![V::default(); max_budget + 1];
+    let mut next = vec![V::default(); max_budget + 1];
+
+    for bundle in split_into_bundles(entries, max_budget) {
+        fill_row(&bundle, &prev, &mut next);
+        swap(&mut prev, &mut next);
+    }
+
+    prev
+}
+
+/// Bounded entries are split into bundles of 1, 2, 4, ... copies, so any count up to the bound is a sum of bundles
+/// and each bundle only needs a 0/1 decision. Bounds are first capped at the number of copies `budget` can pay for,
+/// which also keeps every bundle's cost within the budget.
+fn split_into_bundles<V: Add<Output = V> + Copy>(entries: &[Entry<V>], budget: usize) -> Vec<Bundle<V>> {
+    let mut bundles = Vec::new();
+    for (entry, item) in entries.iter().enumerate() {
+        match item.copies {
+            Copies::Unbounded => {
+                assert!(item.cost > 0, "unbounded entry {entry} costs nothing");
+                bundles.push(Bundle {
+                    entry,
+                    count: 1,
+                    cost: item.cost,
+                    value: item.value,
+                    unbounded: true,
+                });
+            }
+            Copies::Bounded(bound) => {
+                let mut left = bound.min(budget.checked_div(item.cost).unwrap_or(usize::MAX));
+                let mut count = 1;
+                while left > 0 {
+                    let count_here = count.min(left);
+                    bundles.push(Bundle {
+                        entry,
+                        count: count_here,
+                        cost: item.cost * count_here,
+                        value: repeat(item.value, count_here),
+                        unbounded: false,
+                    });
+                    left -= count_here;
+                    count = count.saturating_mul(2);
+                }
+            }
+        }
+    }
+    bundles
+}
+
+/// Compute the DP row after deciding on `bundle` from the row before it.
+fn fill_row<V>(bundle: &Bundle<V>, prev: &[V], next: &mut [V])
+where
+    V: Ord + Add<Output = V> + Copy + Default,
+{
+    let (cost, value) = (bundle.cost, bundle.value);
+    for b in 0..next.len() {
+        next[b] = prev[b];
+        if cost <= b {
+            // Unbounded entries can be picked again on top of the current row.
+            let base = if bundle.unbounded {
+                next[b - cost]
+            } else {
+                prev[b - cost]
+            };
+            next[b] = next[b].max(base + value);
+        }
+    }
+}
+
+/// Above this many bytes, [`solve_2d`] gives up on its DP table and searches instead.
+const MAX_TABLE_BYTES: usize = 64 << 20;
+
+/// An item for [`solve_2d`], which uses up two different resources and can be picked at most once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry2d<V> {
+    pub cost: usize,
+    pub weight: usize,
+    pub value: V,
+}
+
+/// Pick the entries that maximise the total value without spending more than `budget` nor using more than
+/// `max_weight`.
+///
+/// Small instances are solved by DP over both dimensions. Instances whose table would be too big fall back to a
+/// branch-and-bound search, which needs adding a value to be monotone (if `a <= b` then `a + c <= b + c`), as it is
+/// for the lexicographic values this is meant for.
+pub fn solve_2d<V>(entries: &[Entry2d<V>], budget: usize, max_weight: usize) -> Solution<V>
+where
+    V: Ord + Add<Output = V> + Copy + Default,
+{
+    let bytes = (entries.len() + 1)
+        .checked_mul(budget + 1)
+        .and_then(|cells| cells.checked_mul(max_weight + 1))
+        .and_then(|cells| cells.checked_mul(size_of::<V>()));
+
+    match bytes {
+        Some(bytes) if bytes <= MAX_TABLE_BYTES => solve_2d_table(entries, budget, max_weight),
+        _ => BranchAndBound::new(entries, budget, max_weight).solve(budget, max_weight),
+    }
+}
+
+fn solve_2d_table<V>(entries: &[Entry2d<V>], budget: usize, max_weight: usize) -> Solution<V>
+where
+    V: Ord + Add<Output = V> + Copy + Default,
+{
+    // `table[i][b][w]` is the best value using only the first `i` entries with at most `b` spent and `w` used.
+    let layer = (budget + 1) * (max_weight + 1);
+    let at = |b: usize, w: usize| b * (max_weight + 1) + w;
+    let mut table = vec![V::default(); (entries.len() + 1) * layer];
+
+    for (i, entry) in entries.iter().enumerate() {
+        let (done, rest) = table.split_at_mut((i + 1) * layer);
+        let prev = &done[i * layer..];
+        let next = &mut rest[..layer];
+
+        for b in 0..=budget {
+            for w in 0..=max_weight {
+                next[at(b, w)] = prev[at(b, w)];
+                if entry.cost <= b && entry.weight <= w {
+                    next[at(b, w)] = next[at(b, w)].max(prev[at(b - entry.cost, w - entry.weight)] + entry.value);
+                }
+            }
+        }
+    }
+
+    let mut counts = vec![0; entries.len()];
+    let mut cost = 0;
+    let (mut b, mut w) = (budget, max_weight);
+    for i in (1..=entries.len()).rev() {
+        if table[i * layer + at(b, w)] != table[(i - 1) * layer + at(b, w)] {
+            let entry = &entries[i - 1];
+            counts[i - 1] = 1;
+            cost += entry.cost;
+            b -= entry.cost;
+            w -= entry.weight;
+        }
+    }
+
+    Solution {
+        value: table[entries.len() * layer + at(budget, max_weight)],
+        cost,
+        counts,
+    }
+}
+
+/// Depth-first search over take/skip decisions, most valuable entries first, pruned whenever taking every remaining
+/// entry that adds value still can't beat the best solution so far.
+struct BranchAndBound<'e, V> {
+    entries: &'e [Entry2d<V>],
+    order: Vec<usize>,
+    /// `optimistic[k]` is the sum of every value above `V::default()` from `order[k..]`.
+    optimistic: Vec<V>,
+    taken: Vec<usize>,
+    best: Option<(V, Vec<usize>)>,
+}
+
+impl<'e, V> BranchAndBound<'e, V>
+where
+    V: Ord + Add<Output = V> + Copy + Default,
+{
+    fn new(entries: &'e [Entry2d<V>], budget: usize, max_weight: usize) -> Self {
+        let mut order = (0..entries.len())
+            .filter(|&i| entries[i].cost <= budget && entries[i].weight <= max_weight)
+            .collect::<Vec<_>>();
+        order.sort_by(|&a, &b| entries[b].value.cmp(&entries[a].value));
+
+        let mut optimistic = vec![V::default(); order.len() + 1];
+        for k in (0..order.len()).rev() {
+            let value = entries[order[k]].value;
+            optimistic[k] = if value > V::default() {
+                optimistic[k + 1] + value
+            } else {
+                optimistic[k + 1]
+            };
+        }
+
+        Self {
+            entries,
+            order,
+            optimistic,
+            taken: Vec::new(),
+            best: None,
+        }
+    }
+
+    fn solve(mut self, budget: usize, max_weight: usize) -> Solution<V> {
+        self.visit(0, V::default(), budget, max_weight);
+
+        let (value, taken) = self.best.unwrap_or_default();
+        let mut counts = vec![0; self.entries.len()];
+        taken.iter().for_each(|&i| counts[i] = 1);
+
+        Solution {
+            value,
+            cost: taken.iter().map(|&i| self.entries[i].cost).sum(),
+            counts,
+        }
+    }
+
+    fn visit(&mut self, k: usize, value: V, budget: usize, max_weight: usize) {
+        if self.best.as_ref().is_none_or(|(best, _)| value > *best) {
+            self.best = Some((value, self.taken.clone()));
+        }
+
+        if k == self.order.len()
+            || self
+                .best
+                .as_ref()
+                .is_some_and(|(best, _)| value + self.optimistic[k] <= *best)
+        {
+            return;
+        }
+
+        let entry = self.entries[self.order[k]];
+        if entry.cost <= budget && entry.weight <= max_weight {
+            self.taken.push(self.order[k]);
+            self.visit(
+                k + 1,
+                value + entry.value,
+                budget - entry.cost,
+                max_weight - entry.weight,
+            );
+            self.taken.pop();
+        }
+        self.visit(k + 1, value, budget, max_weight);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    /// The best value over every subset of `entries` that fits.
+    fn brute_force(entries: &[Entry2d<u64>], budget: usize, max_weight: usize) -> u64 {
+        (0..1u32 << entries.len())
+            .filter_map(|subset| {
+                let picked = entries
+                    .iter()
+                    .enumerate()
+                    .filter(|&(i, _)| subset & (1 << i) != 0)
+                    .map(|(_, entry)| entry);
+                let (cost, weight, value) =
+                    picked.fold((0, 0, 0), |(c, w, v), e| (c + e.cost, w + e.weight, v + e.value));
+                (cost <= budget && weight <= max_weight).then_some(value)
+            })
+            .max()
+            .unwrap()
+    }
+
+    /// The best value over every combination of copies that fits.
+    fn brute_force_copies(entries: &[Entry<u64>], budget: usize) -> u64 {
+        let Some((entry, rest)) = entries.split_first() else {
+            return 0;
+        };
+        let most = match entry.copies {
+            Copies::Bounded(bound) => bound.min(budget.checked_div(entry.cost).unwrap_or(usize::MAX)),
+            Copies::Unbounded => budget / entry.cost,
+        };
+        (0..=most)
+            .map(|count| count as u64 * entry.value + brute_force_copies(rest, budget - count * entry.cost))
+            .max()
+            .unwrap()
+    }
+
+    fn assert_fits(entries: &[Entry2d<u64>], solution: &Solution<u64>, budget: usize, max_weight: usize) {
+        let picked = || {
+            entries
+                .iter()
+                .zip(&solution.counts)
+                .filter(|&(_, &count)| count == 1)
+                .map(|(e, _)| e)
+        };
+        assert!(solution.counts.iter().all(|&count| count <= 1));
+        assert_eq!(picked().map(|e| e.cost).sum::<usize>(), solution.cost);
+        assert_eq!(picked().map(|e| e.value).sum::<u64>(), solution.value);
+        assert!(solution.cost <= budget);
+        assert!(picked().map(|e| e.weight).sum::<usize>() <= max_weight);
+    }
+
+    fn random_entries(rng: &mut StdRng, scale: u64) -> Vec<Entry2d<u64>> {
+        (0..rng.gen_range(0..12))
+            .map(|_| Entry2d {
+                cost: rng.gen_range(0..10 * scale) as usize,
+                weight: rng.gen_range(0..10 * scale) as usize,
+                value: rng.gen_range(0..50),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn solve_matches_brute_force() {
+        let mut rng = StdRng::seed_from_u64(0xa54f_f53a_5f1d_36f1);
+        for _ in 0..500 {
+            let entries = (0..rng.gen_range(0..6))
+                .map(|_| {
+                    let unbounded = rng.gen_range(0..3) == 0;
+                    Entry {
+                        cost: rng.gen_range(usize::from(unbounded)..8),
+                        value: rng.gen_range(0..30),
+                        copies: if unbounded {
+                            Copies::Unbounded
+                        } else {
+                            Copies::Bounded(rng.gen_range(0..5))
+                        },
+                    }
+                })
+                .collect::<Vec<_>>();
+            let budget = rng.gen_range(0..25);
+
+            let solution = solve(&entries, budget);
+            assert_eq!(solution.value, brute_force_copies(&entries, budget));
+            assert!(solution.cost <= budget);
+            for (entry, &count) in entries.iter().zip(&solution.counts) {
+                if let Copies::Bounded(bound) = entry.copies {
+                    assert!(count <= bound);
+                }
+            }
+            let picked = || entries.iter().zip(&solution.counts);
+            assert_eq!(picked().map(|(e, &count)| e.cost * count).sum::<usize>(), solution.cost);
+            assert_eq!(
+                picked().map(|(e, &count)| e.value * count as u64).sum::<u64>(),
+                solution.value
+            );
+
+            let by_budget = best_by_budget(&entries, budget);
+            for (b, &value) in by_budget.iter().enumerate() {
+                assert_eq!(value, solve(&entries, b).value);
+            }
+        }
+    }
+
+    #[test]
+    fn huge_bounds_are_capped_by_the_budget() {
+        let cheap = Entry {
+            cost: 3,
+            value: 1u64,
+            copies: Copies::Bounded(1 << 40),
+        };
+        let solution = solve(&[cheap], 10);
+        assert_eq!((solution.value, solution.cost, solution.counts), (3, 9, vec![3]));
+        assert_eq!(best_by_budget(&[cheap], 10)[10], 3);
+
+        let free = Entry {
+            cost: 0,
+            value: 2u64,
+            copies: Copies::Bounded(1 << 40),
+        };
+        let solution = solve(&[free, cheap], 7);
+        assert_eq!(
+            (solution.value, solution.cost, solution.counts),
+            ((2 << 40) + 2, 6, vec![1 << 40, 2])
+        );
+
+        let endless = Entry {
+            cost: 0,
+            value: 0u64,
+            copies: Copies::Bounded(usize::MAX),
+        };
+        assert_eq!(solve(&[endless], 5).counts, [0]);
+    }
+
+    #[test]
+    fn solve_2d_matches_brute_force() {
+        let mut rng = StdRng::seed_from_u64(0x6a09_e667_f3bc_c908);
+        for _ in 0..500 {
+            let entries = random_entries(&mut rng, 1);
+            let (budget, max_weight) = (rng.gen_range(0..40), rng.gen_range(0..40));
+
+            let solution = solve_2d(&entries, budget, max_weight);
+            assert_eq!(solution.value, brute_force(&entries, budget, max_weight));
+            assert_fits(&entries, &solution, budget, max_weight);
+        }
+    }
+
+    #[test]
+    fn branch_and_bound_matches_brute_force() {
+        let mut rng = StdRng::seed_from_u64(0xbb67_ae85_84ca_a73b);
+        for _ in 0..500 {
+            // Costs and weights this big make the DP table far too large, so `solve_2d` has to search.
+            let entries = random_entries(&mut rng, 1_000_000);
+            let budget = rng.gen_range(1_000_000..41_000_000);
+            let max_weight = rng.gen_range(1_000_000..41_000_000);
+            assert!((budget + 1) * (max_weight + 1) * size_of::<u64>() > MAX_TABLE_BYTES);
+
+            let solution = solve_2d(&entries, budget, max_weight);
+            assert_eq!(solution.value, brute_force(&entries, budget, max_weight));
+            assert_fits(&entries, &solution, budget, max_weight);
+        }
+    }
+
+    #[test]
+    fn branch_and_bound_agrees_with_table() {
+        let mut rng = StdRng::seed_from_u64(0x3c6e_f372_fe94_f82b);
+        for _ in 0..500 {
+            let entries = random_entries(&mut rng, 1);
+            let (budget, max_weight) = (rng.gen_range(0..40), rng.gen_range(0..40));
+
+            let table = solve_2d_table(&entries, budget, max_weight);
+            let search = BranchAndBound::new(&entries, budget, max_weight).solve(budget, max_weight);
+            assert_eq!(table.value, search.value);
+            assert_fits(&entries, &search, budget, max_weight);
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod frontier;
+pub mod item;
+pub mod knapsack;
@@ -1,80 +1,22 @@
-use std::{cmp::Reverse, mem::swap, ops::Add};
-
-#[derive(Clone, Copy, Eq, Hash, PartialEq)]
-struct Item {
-    quality: usize,
-    cost: usize,
-    unique_materials: usize,
-}
-
-fn knapsack(items: &[Item], money: usize) -> usize {
-    #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Debug)]
-    struct Value {
-        quality: usize,
-        unique_materials: Reverse<usize>,
-    }
-
-    impl Add for Value {
-        type Output = Self;
-
-        fn add(self, rhs: Self) -> Self::Output {
-            Self {
-                quality: self.quality + rhs.quality,
-                unique_materials: Reverse(self.unique_materials.0 + rhs.unique_materials.0),
-            }
-        }
-    }
-
-    impl From<&Item> for Value {
-        fn from(item: &Item) -> Self {
-            Self {
-                quality: item.quality,
-                unique_materials: Reverse(item.unique_materials),
-            }
-        }
-    }
-
-    let mut prev = vec![Value::default(); money + 1];
-    let mut next = vec![Value::default(); money + 1];
-
-    for item in items {
-        for j in 1..=money {
-            if item.cost > j {
-                next[j] = prev[j];
-            } else {
-                next[j] = prev[j].max(prev[j - item.cost] + Value::from(item));
-            }
-        }
-        swap(&mut prev, &mut next);
-    }
-
-    let sol = prev[money];
-    sol.quality * sol.unique_materials.0
-}
+use day14::{frontier::Frontier, item::parse_items};
 
 fn main() {
     let input = include_str!("input.txt");
 
-    let mut items = input
-        .lines()
-        .map(|line| {
-            let mut it = line
-                .split_once(" | ")
-                .unwrap()
-                .1
-                .split(", ")
-                .map(|part| part.split_once(": ").unwrap().1.parse().unwrap());
+    let mut items = parse_items(input);
+    items.sort_by_key(|item| (item.quality, item.cost));
 
-            (it.next().unwrap(), it.next().unwrap(), it.next().unwrap())
-        })
-        .map(|(quality, cost, unique_materials)| Item {
-            quality,
-            cost,
-            unique_materials,
-        })
-        .collect::<Vec<_>>();
+    let frontier = Frontier::new(&items, 300);
 
-    items.sort_by_key(|item| (item.quality, item.cost));
+    // `cargo run -p day14 -- --csv` (or `--json`) exports the quality/cost frontier for plotting.
+    if std::env::args().any(|arg| arg == "--csv") {
+        print!("{}", frontier.to_csv());
+        return;
+    }
+    if std::env::args().any(|arg| arg == "--json") {
+        print!("{}", frontier.to_json());
+        return;
+    }
 
     let part1 = items
         .iter()
@@ -84,9 +26,9 @@ fn main() {
         .sum::<usize>();
     println!("{part1}");
 
-    let part2 = knapsack(&items, 30);
+    let part2 = frontier.best(30).score();
     println!("{part2}");
 
-    let part3 = knapsack(&items, 300);
+    let part3 = frontier.best(frontier.max_budget()).score();
     println!("{part3}");
 }
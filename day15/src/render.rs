@@ -0,0 +1,96 @@
+use std::fmt::Write;
+
+use crate::tree::Tree;
+
+impl Tree<'_> {
+    /// Draw the tree sideways, with the root on the left and right subtrees above left ones.
+    ///
+    /// Each line starts with the node's layer and that layer's sum of ids.
+    pub fn pretty(&self) -> String {
+        let layer_sums = self.layer_sums();
+        let mut out = String::new();
+
+        // Reverse in-order, so that the right subtree comes out above its parent.
+        let mut stack = self.root().map(|root| (root, 0, false)).into_iter().collect::<Vec<_>>();
+        while let Some((idx, layer, expanded)) = stack.pop() {
+            let node = self.node(idx);
+            if !expanded {
+                stack.extend(node.left.map(|left| (left, layer + 1, false)));
+                stack.push((idx, layer, true));
+                stack.extend(node.right.map(|right| (right, layer + 1, false)));
+                continue;
+            }
+
+            let branch = match node.parent.map(|parent| self.node(parent).right == Some(idx)) {
+                None => "",
+                Some(true) => "/-",
+                Some(false) => "\\-",
+            };
+            writeln!(
+                out,
+                "{layer:>3} {:>10} | {:indent$}{branch}{}:{}",
+                layer_sums[layer],
+                "",
+                node.code,
+                node.id,
+                indent = 4 * layer,
+            )
+            .unwrap();
+        }
+
+        out
+    }
+
+    /// Render the tree in Graphviz DOT format, filling each group of highlighted nodes with its colour.
+    pub fn to_dot(&self, highlights: &[(&str, &[usize])]) -> String {
+        let mut dot = String::from("digraph {\n");
+
+        for idx in self.preorder() {
+            let node = self.node(idx);
+            write!(
+                dot,
+                "    n{idx} [label={}",
+                quoted(&format!("{}:{}", node.code, node.id))
+            )
+            .unwrap();
+            if let Some((colour, _)) = highlights.iter().rev().find(|(_, nodes)| nodes.contains(&idx)) {
+                write!(dot, ", style=filled, fillcolor={}", quoted(colour)).unwrap();
+            }
+            dot.push_str("]\n");
+
+            for (child, side) in [(node.left, "L"), (node.right, "R")] {
+                if let Some(child) = child {
+                    writeln!(dot, "    n{idx} -> n{child} [label=\"{side}\"]").unwrap();
+                }
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// A quoted DOT string, so codes with quotes or backslashes in them still parse.
+fn quoted(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tree::{Balancing, Tree};
+
+    #[test]
+    fn labels_are_escaped() {
+        let mut tree = Tree::new(Balancing::None);
+        let root = tree.insert("plain", 5).unwrap();
+        tree.insert("say \"hi\"", 3).unwrap();
+        tree.insert("back\\slash", 8).unwrap();
+
+        let dot = tree.to_dot(&[("light blue", &[root])]);
+        assert!(dot.contains(&format!(
+            "    n{root} [label=\"plain:5\", style=filled, fillcolor=\"light blue\"]\n"
+        )));
+        assert!(dot.contains("[label=\"say \\\"hi\\\":3\"]\n"));
+        assert!(dot.contains("[label=\"back\\\\slash:8\"]\n"));
+    }
+}
@@ -0,0 +1,811 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Display,
+};
+
+/// How the tree keeps itself in shape as nodes are inserted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Balancing {
+    /// Plain BST insertion, as the puzzle describes. Sorted input gives a degenerate tree.
+    None,
+    /// AVL rotations after every insertion, which keep the height logarithmic.
+    Avl,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Node<'a> {
+    pub code: &'a str,
+    pub id: u32,
+
+    pub left: Option<usize>,
+    pub right: Option<usize>,
+
+    pub parent: Option<usize>,
+
+    /// Number of layers in the subtree rooted here.
+    height: usize,
+}
+
+impl<'a> Node<'a> {
+    fn new_leaf(code: &'a str, id: u32, parent: Option<usize>) -> Self {
+        Self {
+            code,
+            id,
+            left: None,
+            right: None,
+            parent,
+            height: 1,
+        }
+    }
+}
+
+/// A binary search tree on ids, stored in a `slab::Slab` arena.
+#[derive(Clone, Debug)]
+pub struct Tree<'a> {
+    arena: slab::Slab<Node<'a>>,
+    root: Option<usize>,
+    balancing: Balancing,
+    by_code: HashMap<&'a str, usize>,
+}
+
+impl<'a> Tree<'a> {
+    pub fn new(balancing: Balancing) -> Self {
+        Self {
+            arena: slab::Slab::new(),
+            root: None,
+            balancing,
+            by_code: HashMap::new(),
+        }
+    }
+
+    pub fn root(&self) -> Option<usize> {
+        self.root
+    }
+
+    pub fn node(&self, idx: usize) -> &Node<'a> {
+        &self.arena[idx]
+    }
+
+    pub fn len(&self) -> usize {
+        self.arena.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.arena.is_empty()
+    }
+
+    /// Insert a node, going right on equal ids, and return its index.
+    ///
+    /// Codes identify nodes for [`Tree::find`] and [`Tree::delete`], so a code that's already in the tree is rejected.
+    pub fn insert(&mut self, code: &'a str, id: u32) -> Result<usize, DuplicateCode<'a>> {
+        if self.by_code.contains_key(code) {
+            return Err(DuplicateCode(code));
+        }
+
+        let Some(mut current) = self.root else {
+            let idx = self.arena.insert(Node::new_leaf(code, id, None));
+            self.root = Some(idx);
+            self.by_code.insert(code, idx);
+            return Ok(idx);
+        };
+
+        let idx = loop {
+            let node = self.arena[current];
+            let child = if id < node.id { node.left } else { node.right };
+            if let Some(child) = child {
+                current = child;
+                continue;
+            }
+
+            let idx = self.arena.insert(Node::new_leaf(code, id, Some(current)));
+            if id < node.id {
+                self.arena[current].left = Some(idx);
+            } else {
+                self.arena[current].right = Some(idx);
+            }
+            break idx;
+        };
+
+        self.by_code.insert(code, idx);
+        self.retrace(Some(current));
+        Ok(idx)
+    }
+
+    /// Remove the node with the given code, returning its id, or `None` if there's no such node.
+    ///
+    /// A node with two children is replaced by its in-order successor, the smallest node in its right subtree.
+    pub fn delete(&mut self, code: &str) -> Option<u32> {
+        let idx = self.by_code.remove(code)?;
+        let node = self.arena.remove(idx);
+
+        // The lowest node whose subtree changed shape, where retracing has to start from.
+        let changed = match (node.left, node.right) {
+            (Some(left), Some(right)) => {
+                let mut successor = right;
+                while let Some(left) = self.arena[successor].left {
+                    successor = left;
+                }
+
+                let changed = if successor == right {
+                    successor
+                } else {
+                    let successor_parent = self.arena[successor].parent;
+                    self.replace_child(successor_parent, successor, self.arena[successor].right);
+                    self.arena[successor].right = Some(right);
+                    self.arena[right].parent = Some(successor);
+                    successor_parent.unwrap()
+                };
+
+                self.arena[successor].left = Some(left);
+                self.arena[left].parent = Some(successor);
+                // Retracing may stop before reaching the successor, which is only right if it inherits this height.
+                self.arena[successor].height = node.height;
+                self.replace_child(node.parent, idx, Some(successor));
+                Some(changed)
+            }
+            (child, None) | (None, child) => {
+                self.replace_child(node.parent, idx, child);
+                node.parent
+            }
+        };
+
+        self.retrace(changed);
+        Some(node.id)
+    }
+
+    /// Walk from `current` up to the root, fixing up heights and, if balancing, rotating unbalanced nodes.
+    fn retrace(&mut self, mut current: Option<usize>) {
+        while let Some(mut idx) = current {
+            let old_height = self.arena[idx].height;
+            self.update_height(idx);
+
+            if self.balancing == Balancing::Avl {
+                idx = self.rebalance(idx);
+            } else if self.arena[idx].height == old_height {
+                // Nothing above can change either.
+                break;
+            }
+
+            current = self.arena[idx].parent;
+        }
+    }
+
+    fn subtree_height(&self, idx: Option<usize>) -> usize {
+        idx.map_or(0, |idx| self.arena[idx].height)
+    }
+
+    fn update_height(&mut self, idx: usize) {
+        let node = self.arena[idx];
+        self.arena[idx].height = 1 + self.subtree_height(node.left).max(self.subtree_height(node.right));
+    }
+
+    fn balance_factor(&self, idx: usize) -> isize {
+        let node = &self.arena[idx];
+        self.subtree_height(node.left) as isize - self.subtree_height(node.right) as isize
+    }
+
+    /// Rotate `idx` back into AVL shape if needed, returning the index of the subtree's new root.
+    fn rebalance(&mut self, idx: usize) -> usize {
+        match self.balance_factor(idx) {
+            2.. => {
+                let left = self.arena[idx].left.unwrap();
+                if self.balance_factor(left) < 0 {
+                    self.rotate_left(left);
+                }
+                self.rotate_right(idx)
+            }
+            ..=-2 => {
+                let right = self.arena[idx].right.unwrap();
+                if self.balance_factor(right) > 0 {
+                    self.rotate_right(right);
+                }
+                self.rotate_left(idx)
+            }
+            _ => idx,
+        }
+    }
+
+    /// Make `new` the child of `parent` that `old` used to be, or the root if `old` was the root.
+    fn replace_child(&mut self, parent: Option<usize>, old: usize, new: Option<usize>) {
+        match parent {
+            None => self.root = new,
+            Some(parent) if self.arena[parent].left == Some(old) => self.arena[parent].left = new,
+            Some(parent) => self.arena[parent].right = new,
+        }
+        if let Some(new) = new {
+            self.arena[new].parent = parent;
+        }
+    }
+
+    fn rotate_left(&mut self, idx: usize) -> usize {
+        let pivot = self.arena[idx].right.unwrap();
+        let inner = self.arena[pivot].left;
+
+        self.replace_child(self.arena[idx].parent, idx, Some(pivot));
+        self.arena[idx].right = inner;
+        if let Some(inner) = inner {
+            self.arena[inner].parent = Some(idx);
+        }
+        self.arena[pivot].left = Some(idx);
+        self.arena[idx].parent = Some(pivot);
+
+        self.update_height(idx);
+        self.update_height(pivot);
+        pivot
+    }
+
+    fn rotate_right(&mut self, idx: usize) -> usize {
+        let pivot = self.arena[idx].left.unwrap();
+        let inner = self.arena[pivot].right;
+
+        self.replace_child(self.arena[idx].parent, idx, Some(pivot));
+        self.arena[idx].left = inner;
+        if let Some(inner) = inner {
+            self.arena[inner].parent = Some(idx);
+        }
+        self.arena[pivot].right = Some(idx);
+        self.arena[idx].parent = Some(pivot);
+
+        self.update_height(idx);
+        self.update_height(pivot);
+        pivot
+    }
+
+    /// Number of layers in the tree.
+    pub fn height(&self) -> usize {
+        self.subtree_height(self.root)
+    }
+
+    /// Sum of the ids in each layer, from the root down.
+    pub fn layer_sums(&self) -> Vec<u32> {
+        let mut layer_sums = vec![0; self.height()];
+        let mut stack = self.root.map(|root| (root, 0)).into_iter().collect::<Vec<_>>();
+        while let Some((idx, layer)) = stack.pop() {
+            let node = &self.arena[idx];
+            layer_sums[layer] += node.id;
+            stack.extend(node.left.into_iter().chain(node.right).map(|child| (child, layer + 1)));
+        }
+        layer_sums
+    }
+
+    /// The codes of the nodes visited while searching for where `id` would be inserted.
+    pub fn search_path(&self, id: u32) -> Vec<&'a str> {
+        let mut path = Vec::new();
+        let mut current = self.root;
+        while let Some(idx) = current {
+            let node = &self.arena[idx];
+            path.push(node.code);
+            current = if id < node.id { node.left } else { node.right };
+        }
+        path
+    }
+
+    pub fn find(&self, code: &str) -> Option<usize> {
+        self.by_code.get(code).copied()
+    }
+
+    /// The node with the given code, followed by each of its ancestors up to the root.
+    pub fn path_to_root(&self, code: &str) -> Vec<usize> {
+        let mut current = self.find(code);
+        std::iter::from_fn(move || {
+            let idx = current?;
+            current = self.arena[idx].parent;
+            Some(idx)
+        })
+        .collect()
+    }
+
+    /// The deepest node that has both `a` and `b` in its subtree.
+    pub fn lowest_common_ancestor(&self, a: &str, b: &str) -> Option<&'a str> {
+        let b_parents = self.path_to_root(b).into_iter().collect::<HashSet<_>>();
+        let lca = self
+            .path_to_root(a)
+            .into_iter()
+            .find(|parent| b_parents.contains(parent))?;
+        Some(self.arena[lca].code)
+    }
+
+    /// The codes of every node with an id in `lo..=hi`, in ascending order of id.
+    pub fn range(&self, lo: u32, hi: u32) -> Vec<&'a str> {
+        let mut codes = Vec::new();
+        let mut stack = self.root.map(|root| (root, false)).into_iter().collect::<Vec<_>>();
+        while let Some((idx, expanded)) = stack.pop() {
+            let node = &self.arena[idx];
+            if expanded {
+                codes.push(node.code);
+                continue;
+            }
+
+            // Rotations can leave equal ids on either side, so only prune subtrees that are strictly out of range.
+            if node.id <= hi {
+                stack.extend(node.right.map(|right| (right, false)));
+            }
+            if (lo..=hi).contains(&node.id) {
+                stack.push((idx, true));
+            }
+            if node.id >= lo {
+                stack.extend(node.left.map(|left| (left, false)));
+            }
+        }
+        codes
+    }
+
+    /// The node with the `n`th smallest id, counting from zero.
+    pub fn nth_smallest(&self, n: usize) -> Option<usize> {
+        self.inorder().nth(n)
+    }
+
+    /// Serialize back to the `CODE | id` input format.
+    ///
+    /// Nodes are written in pre-order, so inserting them again without balancing rebuilds the same shape, as long as
+    /// ids are unique.
+    pub fn to_input(&self) -> String {
+        self.preorder()
+            .map(|idx| format!("{} | {}\n", self.arena[idx].code, self.arena[idx].id))
+            .collect()
+    }
+
+    /// Node indices in pre-order: each node before its left subtree, then its right subtree.
+    pub fn preorder(&self) -> impl Iterator<Item = usize> + '_ {
+        let mut stack = self.root.into_iter().collect::<Vec<_>>();
+        std::iter::from_fn(move || {
+            let idx = stack.pop()?;
+            let node = &self.arena[idx];
+            stack.extend(node.right.into_iter().chain(node.left));
+            Some(idx)
+        })
+    }
+
+    /// Node indices in in-order, which is ascending order of id.
+    pub fn inorder(&self) -> impl Iterator<Item = usize> + '_ {
+        // The flag marks nodes whose children have already been pushed.
+        let mut stack = self.root.map(|root| (root, false)).into_iter().collect::<Vec<_>>();
+        std::iter::from_fn(move || loop {
+            let (idx, expanded) = stack.pop()?;
+            if expanded {
+                return Some(idx);
+            }
+            let node = &self.arena[idx];
+            stack.extend(node.right.map(|right| (right, false)));
+            stack.push((idx, true));
+            stack.extend(node.left.map(|left| (left, false)));
+        })
+    }
+
+    /// Node indices in post-order: each node after both of its subtrees.
+    pub fn postorder(&self) -> impl Iterator<Item = usize> + '_ {
+        let mut stack = self.root.map(|root| (root, false)).into_iter().collect::<Vec<_>>();
+        std::iter::from_fn(move || loop {
+            let (idx, expanded) = stack.pop()?;
+            if expanded {
+                return Some(idx);
+            }
+            let node = &self.arena[idx];
+            stack.push((idx, true));
+            stack.extend(node.right.map(|right| (right, false)));
+            stack.extend(node.left.map(|left| (left, false)));
+        })
+    }
+
+    /// Node indices layer by layer from the root down, each layer from left to right.
+    pub fn level_order(&self) -> impl Iterator<Item = usize> + '_ {
+        let mut queue = self.root.into_iter().collect::<VecDeque<_>>();
+        std::iter::from_fn(move || {
+            let idx = queue.pop_front()?;
+            let node = &self.arena[idx];
+            queue.extend(node.left.into_iter().chain(node.right));
+            Some(idx)
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DuplicateCode<'a>(pub &'a str);
+
+impl Display for DuplicateCode<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "code {:?} is already in the tree", self.0)
+    }
+}
+
+impl std::error::Error for DuplicateCode<'_> {}
+
+/// Binary lifting table answering lowest common ancestor queries in O(log n) each.
+///
+/// This is a snapshot: it must be rebuilt after the tree changes.
+#[derive(Clone, Debug)]
+pub struct LcaIndex {
+    depth: Vec<usize>,
+    /// `up[k][idx]` is the ancestor `2^k` levels above `idx`, or the root if there aren't that many.
+    up: Vec<Vec<usize>>,
+}
+
+impl LcaIndex {
+    pub fn new(tree: &Tree) -> Self {
+        let slots = tree.arena.iter().map(|(idx, _)| idx + 1).max().unwrap_or(0);
+        let mut depth = vec![0; slots];
+        let mut parent = vec![0; slots];
+
+        for idx in tree.level_order() {
+            let node = &tree.arena[idx];
+            match node.parent {
+                Some(p) => {
+                    depth[idx] = depth[p] + 1;
+                    parent[idx] = p;
+                }
+                None => parent[idx] = idx,
+            }
+        }
+
+        let levels = (usize::BITS - tree.height().leading_zeros()).max(1) as usize;
+        let mut up = vec![parent];
+        for k in 1..levels {
+            let prev = &up[k - 1];
+            let next = (0..slots).map(|idx| prev[prev[idx]]).collect();
+            up.push(next);
+        }
+
+        Self { depth, up }
+    }
+
+    pub fn depth(&self, idx: usize) -> usize {
+        self.depth[idx]
+    }
+
+    /// The lowest common ancestor of the nodes at `a` and `b`.
+    pub fn lca(&self, mut a: usize, mut b: usize) -> usize {
+        if self.depth[a] < self.depth[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+
+        let mut diff = self.depth[a] - self.depth[b];
+        for level in &self.up {
+            if diff & 1 == 1 {
+                a = level[a];
+            }
+            diff >>= 1;
+        }
+
+        if a == b {
+            return a;
+        }
+
+        for level in self.up.iter().rev() {
+            if level[a] != level[b] {
+                a = level[a];
+                b = level[b];
+            }
+        }
+        self.up[0][a]
+    }
+}
+
+/// Parse a `CODE | id` line.
+pub fn parse_node(line: &str) -> (&str, u32) {
+    let (code, id) = line.split_once(" | ").unwrap();
+    (code, id.parse().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{
+        rngs::StdRng,
+        seq::{index::sample, SliceRandom},
+        Rng, SeedableRng,
+    };
+
+    use super::*;
+
+    /// `n` distinct ids in random order.
+    fn distinct_ids(rng: &mut StdRng, n: usize) -> Vec<u32> {
+        let mut ids = (0..n as u32).map(|i| i * 3 + 1).collect::<Vec<_>>();
+        ids.shuffle(rng);
+        ids
+    }
+
+    fn codes(n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("N{i}")).collect()
+    }
+
+    fn build<'a>(balancing: Balancing, codes: &'a [String], ids: &[u32]) -> Tree<'a> {
+        let mut tree = Tree::new(balancing);
+        for (code, &id) in codes.iter().zip(ids) {
+            tree.insert(code, id).unwrap();
+        }
+        tree
+    }
+
+    /// Check the links, heights and ordering of every node, and the balance of AVL trees.
+    fn check_invariants(tree: &Tree) {
+        assert_eq!(tree.root.map(|root| tree.arena[root].parent), tree.root.map(|_| None));
+        for (idx, node) in &tree.arena {
+            for child in node.left.into_iter().chain(node.right) {
+                assert_eq!(tree.arena[child].parent, Some(idx));
+            }
+            let (left, right) = (tree.subtree_height(node.left), tree.subtree_height(node.right));
+            assert_eq!(node.height, 1 + left.max(right));
+            if tree.balancing == Balancing::Avl {
+                assert!(left.abs_diff(right) <= 1, "node {} is unbalanced", node.code);
+            }
+            assert_eq!(tree.find(node.code), Some(idx));
+        }
+
+        let ids = tree.inorder().map(|idx| tree.arena[idx].id).collect::<Vec<_>>();
+        assert!(ids.is_sorted());
+        assert_eq!(tree.preorder().count(), tree.len());
+        assert_eq!(tree.level_order().count(), tree.len());
+    }
+
+    /// The largest height an AVL tree with `n` nodes can have.
+    fn avl_height_bound(n: usize) -> usize {
+        (1.4405 * ((n + 2) as f64).log2() - 0.3277).floor() as usize
+    }
+
+    fn in_order_codes<'a>(tree: &Tree<'a>) -> Vec<&'a str> {
+        tree.inorder().map(|idx| tree.node(idx).code).collect()
+    }
+
+    fn is_ancestor(tree: &Tree, ancestor: &str, code: &str) -> bool {
+        tree.path_to_root(code).contains(&tree.find(ancestor).unwrap())
+    }
+
+    #[test]
+    fn both_modes_agree() {
+        let mut rng = StdRng::seed_from_u64(0x510e_527f_ade6_82d1);
+        for _ in 0..200 {
+            let n = rng.gen_range(1..=200);
+            let codes = codes(n);
+            let ids = distinct_ids(&mut rng, n);
+            let plain = build(Balancing::None, &codes, &ids);
+            let avl = build(Balancing::Avl, &codes, &ids);
+
+            for tree in [&plain, &avl] {
+                check_invariants(tree);
+                assert_eq!(tree.len(), n);
+                assert_eq!(tree.layer_sums().iter().sum::<u32>(), ids.iter().sum::<u32>());
+                assert_eq!(tree.layer_sums().len(), tree.height());
+                for (code, &id) in codes.iter().zip(&ids) {
+                    assert_eq!(tree.search_path(id).iter().filter(|&&c| c == code).count(), 1);
+                }
+            }
+            assert!(avl.height() <= avl_height_bound(n));
+            assert!(avl.height() <= plain.height());
+
+            assert_eq!(in_order_codes(&plain), in_order_codes(&avl));
+
+            for _ in 0..20 {
+                let (i, j) = (rng.gen_range(0..n), rng.gen_range(0..n));
+                let (lo, hi) = (ids[i].min(ids[j]), ids[i].max(ids[j]));
+                for tree in [&plain, &avl] {
+                    // In any BST with distinct ids, the LCA is the first node on the way down that splits the two.
+                    let lca = tree.lowest_common_ancestor(&codes[i], &codes[j]).unwrap();
+                    let lca_id = tree.node(tree.find(lca).unwrap()).id;
+                    assert!((lo..=hi).contains(&lca_id));
+                    assert!(is_ancestor(tree, lca, &codes[i]) && is_ancestor(tree, lca, &codes[j]));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn lca_index_matches_lowest_common_ancestor() {
+        let mut rng = StdRng::seed_from_u64(0x9b05_688c_2b3e_6c1f);
+        for _ in 0..100 {
+            let n = rng.gen_range(1..=300);
+            let codes = codes(n);
+            let ids = distinct_ids(&mut rng, n);
+
+            for balancing in [Balancing::None, Balancing::Avl] {
+                let tree = build(balancing, &codes, &ids);
+                let index = LcaIndex::new(&tree);
+                for _ in 0..50 {
+                    let (a, b) = (&codes[rng.gen_range(0..n)], &codes[rng.gen_range(0..n)]);
+                    let lca = index.lca(tree.find(a).unwrap(), tree.find(b).unwrap());
+                    assert_eq!(Some(tree.node(lca).code), tree.lowest_common_ancestor(a, b));
+                    assert_eq!(index.depth(lca) + 1, tree.path_to_root(tree.node(lca).code).len());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn duplicate_codes_are_rejected() {
+        let mut tree = Tree::new(Balancing::None);
+        tree.insert("A", 1).unwrap();
+        assert_eq!(tree.insert("A", 2), Err(DuplicateCode("A")));
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree.node(tree.find("A").unwrap()).id, 1);
+    }
+
+    #[test]
+    fn sorted_input() {
+        for n in [1, 2, 10, 100, 1000] {
+            let codes = codes(n);
+            let ascending = (0..n as u32).collect::<Vec<_>>();
+            let descending = ascending.iter().rev().copied().collect::<Vec<_>>();
+
+            for ids in [&ascending, &descending] {
+                let plain = build(Balancing::None, &codes, ids);
+                let avl = build(Balancing::Avl, &codes, ids);
+                check_invariants(&plain);
+                check_invariants(&avl);
+
+                assert_eq!(plain.height(), n);
+                assert!(avl.height() <= avl_height_bound(n));
+                let ids_in_order = |tree: &Tree| tree.inorder().map(|idx| tree.node(idx).id).collect::<Vec<_>>();
+                assert_eq!(ids_in_order(&plain), ascending);
+                assert_eq!(ids_in_order(&avl), ascending);
+            }
+        }
+    }
+
+    #[test]
+    fn duplicate_ids_go_right() {
+        let codes = codes(5);
+        for balancing in [Balancing::None, Balancing::Avl] {
+            let tree = build(balancing, &codes, &[5, 5, 5, 3, 5]);
+            check_invariants(&tree);
+            let ids = tree.inorder().map(|idx| tree.node(idx).id).collect::<Vec<_>>();
+            assert_eq!(ids, [3, 5, 5, 5, 5]);
+        }
+    }
+
+    fn shape<'a>(tree: &Tree<'a>) -> Vec<(&'a str, Option<&'a str>)> {
+        tree.preorder()
+            .map(|idx| {
+                let node = tree.node(idx);
+                (node.code, node.parent.map(|parent| tree.node(parent).code))
+            })
+            .collect()
+    }
+
+    fn parent_code<'a>(tree: &Tree<'a>, code: &str) -> Option<&'a str> {
+        tree.node(tree.find(code)?).parent.map(|parent| tree.node(parent).code)
+    }
+
+    #[test]
+    fn delete_cases() {
+        //         50
+        //     30      70
+        //   20  40  60  80
+        //             65
+        let ids = [50, 30, 70, 20, 40, 60, 80, 65];
+        let codes = ids.map(|id| format!("N{id}"));
+        let fresh = || build(Balancing::None, &codes, &ids);
+        let remaining = |tree: &Tree| tree.inorder().map(|idx| tree.node(idx).id).collect::<Vec<_>>();
+
+        // A leaf.
+        let mut tree = fresh();
+        assert_eq!(tree.delete("N20"), Some(20));
+        check_invariants(&tree);
+        assert_eq!(tree.node(tree.find("N30").unwrap()).left, None);
+        assert_eq!(remaining(&tree), [30, 40, 50, 60, 65, 70, 80]);
+
+        // A node with only a right child, which takes its place.
+        let mut tree = fresh();
+        assert_eq!(tree.delete("N60"), Some(60));
+        check_invariants(&tree);
+        assert_eq!(parent_code(&tree, "N65"), Some("N70"));
+
+        // Two children, where the successor is the right child itself.
+        let mut tree = fresh();
+        assert_eq!(tree.delete("N70"), Some(70));
+        check_invariants(&tree);
+        assert_eq!(parent_code(&tree, "N80"), Some("N50"));
+        assert_eq!(parent_code(&tree, "N60"), Some("N80"));
+
+        // Two children, where the successor is deeper down and hands its right child to its parent. This is the root.
+        let mut tree = fresh();
+        assert_eq!(tree.delete("N50"), Some(50));
+        check_invariants(&tree);
+        assert_eq!(tree.root().map(|root| tree.node(root).code), Some("N60"));
+        assert_eq!(parent_code(&tree, "N65"), Some("N70"));
+        assert_eq!(remaining(&tree), [20, 30, 40, 60, 65, 70, 80]);
+
+        // Missing codes, and emptying the tree by deleting the root again and again.
+        let mut tree = fresh();
+        assert_eq!(tree.delete("N99"), None);
+        while let Some(root) = tree.root() {
+            let code = tree.node(root).code;
+            tree.delete(code).unwrap();
+            check_invariants(&tree);
+        }
+        assert!(tree.is_empty());
+        assert_eq!(tree.height(), 0);
+    }
+
+    #[test]
+    fn random_deletes_keep_the_tree_valid() {
+        let mut rng = StdRng::seed_from_u64(0x1f83_d9ab_fb41_bd6b);
+        for _ in 0..100 {
+            let n = rng.gen_range(1..=150);
+            let codes = codes(n);
+            let ids = distinct_ids(&mut rng, n);
+
+            for balancing in [Balancing::None, Balancing::Avl] {
+                let mut tree = build(balancing, &codes, &ids);
+                let mut model = codes
+                    .iter()
+                    .zip(&ids)
+                    .map(|(code, &id)| (id, code.as_str()))
+                    .collect::<Vec<_>>();
+                model.sort_unstable();
+
+                for victim in sample(&mut rng, n, n / 2 + 1) {
+                    assert_eq!(tree.delete(&codes[victim]), Some(ids[victim]));
+                    assert_eq!(tree.delete(&codes[victim]), None);
+                    model.retain(|&(id, _)| id != ids[victim]);
+
+                    check_invariants(&tree);
+                    assert_eq!(tree.len(), model.len());
+                    assert_eq!(
+                        in_order_codes(&tree),
+                        model.iter().map(|&(_, code)| code).collect::<Vec<_>>()
+                    );
+                    if balancing == Balancing::Avl {
+                        assert!(tree.height() <= avl_height_bound(tree.len()));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn range_and_nth_smallest() {
+        let mut rng = StdRng::seed_from_u64(0x5be0_cd19_137e_2179);
+        for _ in 0..100 {
+            let n = rng.gen_range(1..=100);
+            let codes = codes(n);
+            let ids = (0..n).map(|_| rng.gen_range(0..50)).collect::<Vec<_>>();
+            let mut sorted = ids.clone();
+            sorted.sort_unstable();
+
+            for balancing in [Balancing::None, Balancing::Avl] {
+                let tree = build(balancing, &codes, &ids);
+                for (i, &id) in sorted.iter().enumerate() {
+                    assert_eq!(tree.nth_smallest(i).map(|idx| tree.node(idx).id), Some(id));
+                }
+                assert_eq!(tree.nth_smallest(n), None);
+
+                let (lo, hi) = (rng.gen_range(0..60), rng.gen_range(0..60));
+                let found = tree.range(lo, hi);
+                let found_ids = found
+                    .iter()
+                    .map(|code| tree.node(tree.find(code).unwrap()).id)
+                    .collect::<Vec<_>>();
+                let expected = sorted
+                    .iter()
+                    .copied()
+                    .filter(|id| (lo..=hi).contains(id))
+                    .collect::<Vec<_>>();
+                assert_eq!(found_ids, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn to_input_round_trips() {
+        let mut rng = StdRng::seed_from_u64(0xcbbb_9d5d_c105_9ed8);
+        for _ in 0..100 {
+            let n = rng.gen_range(1..=200);
+            let codes = codes(n);
+            let ids = distinct_ids(&mut rng, n);
+
+            for balancing in [Balancing::None, Balancing::Avl] {
+                let mut tree = build(balancing, &codes, &ids);
+                for code in codes.iter().take(n / 4) {
+                    tree.delete(code);
+                }
+
+                let input = tree.to_input();
+                let mut rebuilt = Tree::new(Balancing::None);
+                for (code, id) in input.lines().map(parse_node) {
+                    rebuilt.insert(code, id).unwrap();
+                }
+                check_invariants(&rebuilt);
+                assert_eq!(shape(&rebuilt), shape(&tree));
+                assert_eq!(rebuilt.to_input(), input);
+            }
+        }
+    }
+}
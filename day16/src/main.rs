@@ -211,9 +211,17 @@ fn main() {
     let part1: u64 = sorted_absorption.into_iter().rev().take(2).map(u64::from).product();
     println!("{part1}");
 
-    let part2: u128 = cube.grids_part2.iter().map(|grid| u128::from(dominant_sum(grid))).product();
+    let part2: u128 = cube
+        .grids_part2
+        .iter()
+        .map(|grid| u128::from(dominant_sum(grid)))
+        .product();
     println!("{part2}");
 
-    let part3: u128 = cube.grids_part3.iter().map(|grid| u128::from(dominant_sum(grid))).product();
+    let part3: u128 = cube
+        .grids_part3
+        .iter()
+        .map(|grid| u128::from(dominant_sum(grid)))
+        .product();
     println!("{part3}");
 }
@@ -138,9 +138,7 @@ fn feasible_space() -> impl Iterator<Item = Vec4> {
 }
 
 fn in_bounds((x, y, z): Vec3) -> bool {
-    (0..X_SIZE).contains(&x)
-        && (0..Y_SIZE).contains(&y)
-        && (0..Z_SIZE).contains(&z)
+    (0..X_SIZE).contains(&x) && (0..Y_SIZE).contains(&y) && (0..Z_SIZE).contains(&z)
 }
 
 fn solve_part2<const MAX_T: N>(debris: &[DebrisPiece]) -> impl std::fmt::Display {
@@ -168,7 +166,7 @@ fn solve_part2<const MAX_T: N>(debris: &[DebrisPiece]) -> impl std::fmt::Display
                 .into_iter()
                 .filter(|&p| in_bounds(p))
                 .filter(|&p| p == INITIAL_POSITION || !debris_at_t.contains(vec3_to_idx(p)))
-                .filter_map(move |p| Some(((p, t + 1), 1)))
+                .map(move |p| ((p, t + 1), 1))
         },
         |&(p, _)| p.0.abs_diff(TARGET_POSITION.0) + p.1.abs_diff(TARGET_POSITION.1) + p.2.abs_diff(TARGET_POSITION.2),
         |&(p, _)| p == TARGET_POSITION,